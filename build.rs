@@ -0,0 +1,77 @@
+//! Generate the table of the inputs compiled into the binary by the `embedded-inputs` feature.
+//!
+//! The inputs of 2020 are the `NN.txt` files at the root of `inputs/`, the ones of the other
+//! years are in `inputs/YEAR/`, as read by `Directory`.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+fn main() -> io::Result<()> {
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_EMBEDDED_INPUTS").is_none() {
+        return Ok(());
+    }
+
+    let root = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("inputs");
+    // A directory is watched recursively, so that new inputs are picked up
+    println!("cargo:rerun-if-changed={}", root.display());
+
+    let mut inputs = Vec::new();
+    if root.is_dir() {
+        inputs.extend(
+            days(&root)?
+                .into_iter()
+                .map(|(day, path)| (2020, day, path)),
+        );
+        for entry in fs::read_dir(&root)? {
+            let path = entry?.path();
+            let year = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .filter(|name| name.len() == 4)
+                .and_then(|name| name.parse::<u32>().ok());
+            if let (Some(year), true) = (year, path.is_dir()) {
+                inputs.extend(
+                    days(&path)?
+                        .into_iter()
+                        .map(|(day, path)| (year, day, path)),
+                );
+            }
+        }
+    }
+    inputs.sort();
+
+    let mut table = String::from("&[\n");
+    for (year, day, path) in inputs {
+        table.push_str(&format!(
+            "    ({}, {}, include_str!({:?})),\n",
+            year,
+            day,
+            path.display().to_string()
+        ));
+    }
+    table.push(']');
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("embedded_inputs.rs");
+    fs::write(out, table)
+}
+
+/// The `NN.txt` files of a directory, with their day.
+fn days(dir: &Path) -> io::Result<Vec<(u32, PathBuf)>> {
+    let mut days = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let day = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".txt"))
+            .filter(|day| day.len() == 2)
+            .and_then(|day| day.parse::<u32>().ok());
+        if let (Some(day), true) = (day, path.is_file()) {
+            days.push((day, path));
+        }
+    }
+    Ok(days)
+}
@@ -0,0 +1,34 @@
+#!/bin/sh
+# Build the seed corpus of every fuzz target from the inputs of the repository.
+#
+# The whole inputs seed the parse_dayNN targets, and their lines seed the targets of the
+# parsers of a single line or program.
+set -eu
+
+cd "$(dirname "$0")"
+inputs=../inputs
+
+for input in "$inputs"/[0-9][0-9].txt "$inputs"/[0-9][0-9].example.txt; do
+    [ -f "$input" ] || continue
+    name=$(basename "$input" .txt)
+    day=${name%%.*}
+    mkdir -p "corpus/parse_day$day"
+    cp "$input" "corpus/parse_day$day/$name"
+done
+
+# Seed a target with each line of the input of a day
+seed_lines() {
+    target=$1
+    day=$2
+    [ -f "$inputs/$day.txt" ] || return 0
+    mkdir -p "corpus/$target"
+    split -l 1 -a 4 "$inputs/$day.txt" "corpus/$target/$day-"
+}
+
+seed_lines password_entry 02
+seed_lines ticket 05
+seed_lines assembly_instruction 08
+seed_lines navigation_instruction 12
+
+mkdir -p corpus/assembly_emulator
+cp "$inputs/08.txt" corpus/assembly_emulator/08
@@ -0,0 +1,206 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "count-allocs")]
+pub use counting::CountingAllocator;
+
+/// The allocations made by a step of a challenge.
+///
+/// Only measured with the `count-allocs` feature, which installs [`CountingAllocator`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct AllocStats {
+    /// The number of allocations, including reallocations.
+    pub allocations: u64,
+    /// The total size of the allocations.
+    pub bytes: u64,
+    /// The maximum size of the memory allocated by the step and still in use at the same time.
+    pub peak_bytes: u64,
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocs, {} (peak {})",
+            self.allocations,
+            format_bytes(self.bytes),
+            format_bytes(self.peak_bytes)
+        )
+    }
+}
+
+/// Format a size with a binary unit, e.g. `1.5 KiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Run `f`, and return the allocations it made on this thread if they are counted.
+///
+/// Measurements do not nest: `f` must not call `measure`.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    #[cfg(feature = "count-allocs")]
+    {
+        let (result, stats) = counting::measure(f);
+        (result, Some(stats))
+    }
+
+    #[cfg(not(feature = "count-allocs"))]
+    {
+        (f(), None)
+    }
+}
+
+#[cfg(feature = "count-allocs")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    use super::AllocStats;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    /// The system allocator, counting the allocations of each thread.
+    pub struct CountingAllocator;
+
+    #[derive(Copy, Clone)]
+    struct Counters {
+        allocations: u64,
+        bytes: u64,
+        /// Can be negative, as memory can be freed by another thread than the one allocating it.
+        live: i64,
+        peak: i64,
+    }
+
+    thread_local! {
+        // No destructor and a const initializer: usable from the allocator itself.
+        static COUNTERS: Cell<Counters> = const {
+            Cell::new(Counters {
+                allocations: 0,
+                bytes: 0,
+                live: 0,
+                peak: 0,
+            })
+        };
+    }
+
+    fn update(f: impl FnOnce(&mut Counters)) {
+        // Fails only while the thread is being destroyed
+        let _ = COUNTERS.try_with(|cell| {
+            let mut counters = cell.get();
+            f(&mut counters);
+            cell.set(counters);
+        });
+    }
+
+    fn record_alloc(size: usize) {
+        update(|c| {
+            c.allocations += 1;
+            c.bytes += size as u64;
+            c.live += size as i64;
+            c.peak = c.peak.max(c.live);
+        });
+    }
+
+    fn record_dealloc(size: usize) {
+        update(|c| c.live -= size as i64);
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                record_alloc(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                record_alloc(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            record_dealloc(layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                record_dealloc(layout.size());
+                record_alloc(new_size);
+            }
+            new_ptr
+        }
+    }
+
+    pub fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+        let before = COUNTERS.with(|cell| {
+            let mut counters = cell.get();
+            counters.peak = counters.live;
+            cell.set(counters);
+            counters
+        });
+        let result = f();
+        let after = COUNTERS.with(Cell::get);
+
+        let stats = AllocStats {
+            allocations: after.allocations - before.allocations,
+            bytes: after.bytes - before.bytes,
+            peak_bytes: (after.peak - before.live).max(0) as u64,
+        };
+        (result, stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(12), "12 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[cfg(not(feature = "count-allocs"))]
+    #[test]
+    fn test_measure_without_counting() {
+        assert_eq!(measure(|| vec![0u8; 100].len()), (100, None));
+    }
+
+    #[cfg(feature = "count-allocs")]
+    #[test]
+    fn test_measure() {
+        let (_, stats) = measure(|| {
+            let first = vec![0u8; 1000];
+            drop(first);
+            let mut second = Vec::<u8>::with_capacity(600);
+            second.reserve_exact(1200);
+            second.capacity()
+        });
+        let stats = stats.unwrap();
+
+        assert_eq!(stats.allocations, 3);
+        assert_eq!(stats.bytes, 1000 + 600 + 1200);
+        assert_eq!(stats.peak_bytes, 1200);
+    }
+}
@@ -0,0 +1,264 @@
+use std::cmp::Ordering;
+use std::convert::{Infallible, TryFrom};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::{Serialize, Serializer};
+
+/// The answer of a part.
+///
+/// The conversions store numbers in the smallest variant that fits them, but numbers compare
+/// by value whatever their variant.
+#[derive(Clone, Debug)]
+pub enum Answer {
+    Integer(i64),
+    /// An integer that does not fit in an `i64`.
+    BigInteger(i128),
+    /// A single line of text.
+    Text(String),
+    /// Several lines of text, such as letters drawn in ASCII art.
+    Block(Vec<String>),
+}
+
+impl Answer {
+    /// The answer as a number, if it is one.
+    pub fn as_i128(&self) -> Option<i128> {
+        match self {
+            Answer::Integer(n) => Some(i128::from(*n)),
+            Answer::BigInteger(n) => Some(*n),
+            Answer::Text(_) | Answer::Block(_) => None,
+        }
+    }
+
+    /// Are the answers equal, or written the same way?
+    ///
+    /// A challenge may answer a number as text, while the same answer read from a file is a number.
+    pub fn matches(&self, other: &Answer) -> bool {
+        self == other || self.to_string() == other.to_string()
+    }
+
+    /// Compare two numeric answers, `None` if one of them is not a number.
+    pub fn numeric_cmp(&self, other: &Answer) -> Option<Ordering> {
+        Some(self.as_i128()?.cmp(&other.as_i128()?))
+    }
+
+    /// The answer on a single line, to fit in a table.
+    pub fn summary(&self) -> String {
+        match self {
+            Answer::Block(lines) => format!("[{} lines]", lines.len()),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Answer) -> bool {
+        match (self, other) {
+            (Answer::Text(a), Answer::Text(b)) => a == b,
+            (Answer::Block(a), Answer::Block(b)) => a == b,
+            _ => self.as_i128().is_some() && self.as_i128() == other.as_i128(),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl Hash for Answer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Answer::Integer(_) | Answer::BigInteger(_) => (0u8, self.as_i128()).hash(state),
+            Answer::Text(text) => (1u8, text).hash(state),
+            Answer::Block(lines) => (2u8, lines).hash(state),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::BigInteger(n) => write!(f, "{}", n),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Block(lines) => write!(f, "{}", lines.join("\n")),
+        }
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(n: i128) -> Self {
+        i64::try_from(n).map_or(Answer::BigInteger(n), Answer::Integer)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(n: u128) -> Self {
+        i128::try_from(n).map_or_else(|_| Answer::Text(n.to_string()), Answer::from)
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($int:ty),*) => {
+        $(
+            impl From<$int> for Answer {
+                fn from(n: $int) -> Self {
+                    Answer::from(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        if text.contains('\n') {
+            Answer::Block(text.lines().map(str::to_string).collect())
+        } else {
+            Answer::Text(text)
+        }
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::from(text.to_string())
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(lines: Vec<String>) -> Self {
+        Answer::Block(lines)
+    }
+}
+
+/// Numbers written the way they are displayed are parsed as numbers, anything else as text:
+/// `0123` or `+5` stay text, as a challenge answering them would.
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.parse::<i128>() {
+            Ok(n) if n.to_string() == s => Answer::from(n),
+            _ => Answer::from(s),
+        })
+    }
+}
+
+/// Integers are serialized as numbers when they fit in an `i64`, everything else as a string.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Integer(n) => serializer.serialize_i64(*n),
+            _ => serializer.collect_str(self),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Answer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(AnswerVisitor)
+    }
+}
+
+struct AnswerVisitor;
+
+impl Visitor<'_> for AnswerVisitor {
+    type Value = Answer;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "an integer or a string")
+    }
+
+    fn visit_i64<E: de::Error>(self, n: i64) -> Result<Answer, E> {
+        Ok(Answer::from(n))
+    }
+
+    fn visit_u64<E: de::Error>(self, n: u64) -> Result<Answer, E> {
+        Ok(Answer::from(n))
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Answer, E> {
+        let Ok(answer) = s.parse();
+        Ok(answer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_integers() {
+        assert_eq!(Answer::from(42u32), Answer::Integer(42));
+        assert_eq!(Answer::from(-3i32), Answer::Integer(-3));
+        assert_eq!(
+            Answer::from(u64::MAX),
+            Answer::BigInteger(i128::from(u64::MAX))
+        );
+        assert_eq!(Answer::from(i128::from(7u8)), Answer::Integer(7));
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("1157018619904".parse(), Ok(Answer::Integer(1157018619904)));
+        assert_eq!("abc".parse(), Ok(Answer::Text("abc".to_string())));
+        assert_eq!("0123".parse(), Ok(Answer::Text("0123".to_string())));
+        assert_eq!("+5".parse(), Ok(Answer::Text("+5".to_string())));
+        assert_eq!("-5".parse(), Ok(Answer::Integer(-5)));
+        assert_eq!(
+            Answer::from("#..#\n####\n"),
+            Answer::Block(vec!["#..#".to_string(), "####".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_eq_by_value() {
+        use std::collections::HashSet;
+
+        assert_eq!(Answer::BigInteger(5), Answer::Integer(5));
+        assert_ne!(Answer::BigInteger(5), Answer::Text("5".to_string()));
+        let set: HashSet<_> = vec![Answer::BigInteger(5), Answer::Integer(5)]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(Answer::from("42").matches(&Answer::Integer(42)));
+        assert!(!Answer::from("42").matches(&Answer::Integer(43)));
+    }
+
+    #[test]
+    fn test_numeric_cmp() {
+        assert_eq!(
+            Answer::from(10u8).numeric_cmp(&Answer::from(u64::MAX)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(Answer::from(1u8).numeric_cmp(&Answer::from("1a")), None);
+    }
+
+    #[test]
+    fn test_serde() {
+        let answers = vec![
+            Answer::Integer(12),
+            Answer::BigInteger(i128::from(u64::MAX) + 1),
+            Answer::Text("abc".to_string()),
+        ];
+
+        let json = serde_json::to_string(&answers).unwrap();
+        assert_eq!(json, r#"[12,"18446744073709551616","abc"]"#);
+        assert_eq!(serde_json::from_str::<Vec<Answer>>(&json).unwrap(), answers);
+        assert_eq!(
+            serde_json::from_str::<Answer>(r#""12""#).unwrap(),
+            Answer::Integer(12)
+        );
+        assert_eq!(
+            serde_json::from_str::<Answer>(r#""0123""#).unwrap(),
+            Answer::Text("0123".to_string())
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{table, Answer, Error, PartReport};
+
+/// The default location of the answer store, relative to the working directory.
+pub const DEFAULT_ANSWERS_PATH: &str = "answers.toml";
+
+/// The known-correct answers of the days, stored in a TOML file such as:
+///
+/// ```toml
+/// [day01]
+/// part1 = 514579
+/// part2 = "ABCDEF"
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct AnswerStore {
+    days: BTreeMap<String, DayAnswers>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+struct DayAnswers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part1: Option<Answer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part2: Option<Answer>,
+}
+
+impl DayAnswers {
+    fn part_mut(&mut self, part: u8) -> Result<&mut Option<Answer>, Error> {
+        match part {
+            1 => Ok(&mut self.part1),
+            2 => Ok(&mut self.part2),
+            _ => Err(Error::InvalidPart(part)),
+        }
+    }
+}
+
+impl AnswerStore {
+    /// Load the store from a file. A missing file is an empty store.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::from_toml(&content)
+                .with_context(|| format!("while parsing {}", path.display())),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error).with_context(|| format!("while reading {}", path.display())),
+        }
+    }
+
+    /// Save the store to a file, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).with_context(|| format!("while creating {}", dir.display()))?;
+        }
+        fs::write(path, self.to_toml()?)
+            .with_context(|| format!("while writing {}", path.display()))
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string(self)?)
+    }
+
+    pub fn get(&self, day: u32, part: u8) -> Option<&Answer> {
+        let answers = self.days.get(&day_key(day))?;
+        match part {
+            1 => answers.part1.as_ref(),
+            2 => answers.part2.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Set the answer of a part, which must be 1 or 2.
+    pub fn set(&mut self, day: u32, part: u8, answer: impl Into<Answer>) -> Result<(), Error> {
+        let key = day_key(day);
+        let mut answers = self.days.get(&key).cloned().unwrap_or_default();
+        *answers.part_mut(part)? = Some(answer.into());
+        self.days.insert(key, answers);
+        Ok(())
+    }
+
+    /// Record the answers of the successful parts.
+    pub fn record(&mut self, reports: &[PartReport]) -> Result<(), Error> {
+        for report in reports {
+            if let Ok(answer) = &report.answer {
+                self.set(report.day, report.part, answer.clone())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Compare the answers of the reports with the known answers.
+    pub fn check(&self, reports: &[PartReport]) -> Vec<Check> {
+        reports
+            .iter()
+            .map(|report| {
+                let expected = self.get(report.day, report.part).cloned();
+                let status = match (&report.answer, &expected) {
+                    (Err(error), _) => CheckStatus::Failed(error.to_string()),
+                    (Ok(_), None) => CheckStatus::Unknown,
+                    (Ok(actual), Some(expected)) if actual.matches(expected) => CheckStatus::Match,
+                    (Ok(_), Some(_)) => CheckStatus::Mismatch,
+                };
+
+                Check {
+                    day: report.day,
+                    part: report.part,
+                    expected,
+                    actual: report.answer.as_ref().ok().cloned(),
+                    status,
+                }
+            })
+            .collect()
+    }
+}
+
+fn day_key(day: u32) -> String {
+    format!("day{:02}", day)
+}
+
+/// The comparison of the answer of a part with the known answer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Check {
+    pub day: u32,
+    pub part: u8,
+    pub expected: Option<Answer>,
+    pub actual: Option<Answer>,
+    pub status: CheckStatus,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CheckStatus {
+    /// The answer is the known answer.
+    Match,
+    /// The answer differs from the known answer.
+    Mismatch,
+    /// There is no known answer for this part.
+    Unknown,
+    /// The part could not be solved.
+    Failed(String),
+}
+
+impl CheckStatus {
+    /// Is this a regression, i.e. a mismatch or a failure?
+    pub fn is_regression(&self) -> bool {
+        matches!(self, CheckStatus::Mismatch | CheckStatus::Failed(_))
+    }
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckStatus::Match => write!(f, "ok"),
+            CheckStatus::Mismatch => write!(f, "MISMATCH"),
+            CheckStatus::Unknown => write!(f, "unknown"),
+            CheckStatus::Failed(error) => write!(f, "FAILED: {}", error),
+        }
+    }
+}
+
+/// A table of checks.
+pub struct CheckSummary<'a>(pub &'a [Check]);
+
+impl fmt::Display for CheckSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = ["Day", "Part", "Expected", "Actual", "Status"];
+        let rows: Vec<[String; 5]> = self
+            .0
+            .iter()
+            .map(|check| {
+                [
+                    check.day.to_string(),
+                    check.part.to_string(),
+                    check
+                        .expected
+                        .as_ref()
+                        .map_or("-".to_string(), Answer::summary),
+                    check
+                        .actual
+                        .as_ref()
+                        .map_or("-".to_string(), Answer::summary),
+                    check.status.to_string(),
+                ]
+            })
+            .collect();
+
+        table::write_table(f, header, &rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(day: u32, part: u8, answer: Result<&str>) -> PartReport {
+        PartReport {
+            day,
+            part,
+            variant: crate::DEFAULT_VARIANT.to_string(),
+            input: String::new(),
+            answer: answer
+                .map(|answer| answer.parse().unwrap())
+                .map_err(|e| crate::Error::solve(day, part, e)),
+            parse_time: None,
+            solve_time: None,
+            parse_alloc: None,
+            solve_alloc: None,
+            shared_parse: true,
+            jobs: 1,
+        }
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let mut store = AnswerStore::default();
+        store.set(1, 1, 514579).unwrap();
+        store.set(12, 2, "ABC").unwrap();
+
+        let content = store.to_toml().unwrap();
+        assert_eq!(
+            content,
+            "[day01]\npart1 = 514579\n\n[day12]\npart2 = \"ABC\"\n"
+        );
+        assert_eq!(AnswerStore::from_toml(&content).unwrap(), store);
+    }
+
+    #[test]
+    fn test_numbers_as_strings() {
+        let store = AnswerStore::from_toml("[day01]\npart1 = \"514579\"\n").unwrap();
+        assert_eq!(store.get(1, 1), Some(&Answer::Integer(514579)));
+    }
+
+    #[test]
+    fn test_check() {
+        let mut store = AnswerStore::default();
+        store.set(1, 1, 1).unwrap();
+        store.set(1, 2, 2).unwrap();
+        store.set(2, 1, 3).unwrap();
+
+        let reports = [
+            report(1, 1, Ok("1")),
+            report(1, 2, Ok("20")),
+            report(2, 1, Err(anyhow::anyhow!("oops"))),
+            report(2, 2, Ok("4")),
+        ];
+        let statuses: Vec<_> = store
+            .check(&reports)
+            .into_iter()
+            .map(|c| c.status)
+            .collect();
+
+        assert_eq!(
+            statuses,
+            vec![
+                CheckStatus::Match,
+                CheckStatus::Mismatch,
+                CheckStatus::Failed("oops".to_string()),
+                CheckStatus::Unknown,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_record() {
+        let mut store = AnswerStore::default();
+        store
+            .record(&[
+                report(3, 1, Ok("7")),
+                report(3, 2, Err(anyhow::anyhow!("no"))),
+            ])
+            .unwrap();
+
+        assert_eq!(store.get(3, 1), Some(&Answer::Integer(7)));
+        assert_eq!(store.get(3, 2), None);
+    }
+
+    #[test]
+    fn test_set_invalid_part() {
+        let mut store = AnswerStore::default();
+        assert!(matches!(store.set(3, 3, 7), Err(Error::InvalidPart(3))));
+        assert_eq!(store, AnswerStore::default());
+    }
+}
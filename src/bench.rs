@@ -0,0 +1,289 @@
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::alloc::format_bytes;
+use crate::runner::part_label;
+use crate::{AllocStats, Error};
+
+/// How long to measure each step of a challenge.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BenchConfig {
+    /// Time spent running the function before starting to record samples.
+    pub warmup: Duration,
+    /// Time after which no more samples are recorded.
+    pub budget: Duration,
+    /// Minimum number of samples, even if it exceeds the budget.
+    pub min_samples: usize,
+    /// Maximum number of samples, even if the budget is not spent.
+    pub max_samples: usize,
+    /// Minimum duration of a sample: the fast steps are run in batches lasting at least this
+    /// long, so that the resolution and the cost of the timer do not weigh on them.
+    pub sample_time: Duration,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            warmup: Duration::from_millis(200),
+            budget: Duration::from_secs(1),
+            min_samples: 10,
+            max_samples: 100_000,
+            sample_time: Duration::from_millis(1),
+        }
+    }
+}
+
+/// Statistics over the samples of a benchmark, as durations of a single run.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Compute the statistics of a non-empty list of samples.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "Need at least one sample");
+        samples.sort_unstable();
+
+        let n = samples.len();
+        let nanos: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+        let mean = nanos.iter().sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+        // Nearest-rank percentile
+        let p95 = samples[((n as f64 * 0.95).ceil() as usize).max(1) - 1];
+
+        Self {
+            samples: n,
+            min: samples[0],
+            median,
+            mean: Duration::from_nanos(mean.round() as u64),
+            p95,
+            stddev: Duration::from_nanos(variance.sqrt().round() as u64),
+        }
+    }
+}
+
+/// Run `f` repeatedly according to `config`, and return the statistics of its duration.
+///
+/// The warm-up also estimates the duration of a run, to size the batches of runs timed as a
+/// single sample.
+pub fn measure<T, F>(config: &BenchConfig, mut f: F) -> Stats
+where
+    F: FnMut() -> T,
+{
+    let mut warmup_runs = 0;
+    let warmup_start = Instant::now();
+    let warmup = loop {
+        black_box(f());
+        warmup_runs += 1;
+        let elapsed = warmup_start.elapsed();
+        if elapsed >= config.warmup {
+            break elapsed;
+        }
+    };
+    let batch = batch_size(config.sample_time, warmup, warmup_runs);
+
+    let mut samples = Vec::new();
+    let start = Instant::now();
+    while samples.len() < config.max_samples
+        && (samples.len() < config.min_samples || start.elapsed() < config.budget)
+    {
+        let sample_start = Instant::now();
+        for _ in 0..batch {
+            black_box(f());
+        }
+        samples.push(sample_start.elapsed() / batch);
+    }
+
+    Stats::from_samples(samples)
+}
+
+/// The number of runs lasting at least `sample_time`, if `runs` took `elapsed`.
+fn batch_size(sample_time: Duration, elapsed: Duration, runs: u64) -> u32 {
+    let batch = sample_time.as_nanos() * u128::from(runs) / elapsed.as_nanos().max(1);
+    batch.clamp(1, u128::from(u32::MAX)) as u32
+}
+
+/// The benchmark of every variant of both parts of a day.
+#[derive(Debug)]
+pub struct DayBench {
+    pub day: u32,
+    pub parse: Stats,
+    /// The allocations of a single parse, if they are counted.
+    pub parse_alloc: Option<AllocStats>,
+    /// The parts in order, each with its default variant first.
+    pub parts: Vec<PartBench>,
+}
+
+/// The benchmark of a variant of a part.
+#[derive(Debug)]
+pub struct PartBench {
+    pub part: u8,
+    pub variant: String,
+    pub stats: Result<Stats, Error>,
+    /// The allocations of a single run, if they are counted.
+    pub alloc: Option<AllocStats>,
+}
+
+impl PartBench {
+    /// The name of the step, such as `Part 2 (telly)` for an alternative variant.
+    pub fn name(&self) -> String {
+        format!("Part {}", part_label(self.part, &self.variant))
+    }
+}
+
+impl fmt::Display for DayBench {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let steps: Vec<_> =
+            std::iter::once(("Parse".to_string(), Ok(&self.parse), self.parse_alloc))
+                .chain(
+                    self.parts
+                        .iter()
+                        .map(|p| (p.name(), p.stats.as_ref(), p.alloc)),
+                )
+                .collect();
+        let title = format!("Day {}", self.day);
+        let width = steps
+            .iter()
+            .map(|(name, _, _)| name.len())
+            .fold(title.len(), usize::max);
+        // The allocations are only shown when they are counted
+        let show_allocs = self.parse_alloc.is_some();
+
+        write!(
+            f,
+            "{:<width$} | {:>7} | {:>10} | {:>10} | {:>10} | {:>10} | {:>10}",
+            title,
+            "Samples",
+            "Min",
+            "Median",
+            "Mean",
+            "P95",
+            "Std dev",
+            width = width
+        )?;
+        if show_allocs {
+            write!(f, " | {:>7} | {:>10} | {:>10}", "Allocs", "Bytes", "Peak")?;
+        }
+        writeln!(f)?;
+
+        for (name, stats, alloc) in &steps {
+            match stats {
+                Ok(stats) => write!(
+                    f,
+                    "{:<width$} | {:>7} | {:>10} | {:>10} | {:>10} | {:>10} | {:>10}",
+                    name,
+                    stats.samples,
+                    format!("{:.2?}", stats.min),
+                    format!("{:.2?}", stats.median),
+                    format!("{:.2?}", stats.mean),
+                    format!("{:.2?}", stats.p95),
+                    format!("{:.2?}", stats.stddev),
+                    width = width
+                )?,
+                Err(error) => write!(f, "{:<width$} | error: {}", name, error, width = width)?,
+            }
+            if let (true, Some(alloc)) = (show_allocs, alloc) {
+                write!(
+                    f,
+                    " | {:>7} | {:>10} | {:>10}",
+                    alloc.allocations,
+                    format_bytes(alloc.bytes),
+                    format_bytes(alloc.peak_bytes)
+                )?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(ms: &[u64]) -> Vec<Duration> {
+        ms.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn test_stats_from_samples() {
+        let stats = Stats::from_samples(millis(&[4, 1, 3, 2, 5]));
+
+        assert_eq!(stats.samples, 5);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.mean, Duration::from_millis(3));
+        assert_eq!(stats.p95, Duration::from_millis(5));
+        // sqrt(2.5) ms
+        assert_eq!(stats.stddev, Duration::from_nanos(1_581_139));
+    }
+
+    #[test]
+    fn test_stats_even_median() {
+        let stats = Stats::from_samples(millis(&[1, 2, 3, 4]));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+    }
+
+    #[test]
+    fn test_measure_respects_limits() {
+        let config = BenchConfig {
+            warmup: Duration::from_millis(0),
+            budget: Duration::from_secs(10),
+            min_samples: 1,
+            max_samples: 42,
+            sample_time: Duration::from_millis(0),
+        };
+
+        let mut calls = 0;
+        let stats = measure(&config, || calls += 1);
+
+        assert_eq!(stats.samples, 42);
+        // One warm-up call
+        assert_eq!(calls, 43);
+    }
+
+    #[test]
+    fn test_batch_size() {
+        let millis = Duration::from_millis;
+        assert_eq!(batch_size(millis(1), millis(10), 1000), 100);
+        assert_eq!(batch_size(millis(1), millis(10), 1), 1);
+        assert_eq!(batch_size(millis(1), Duration::ZERO, 1), 1_000_000);
+        assert_eq!(batch_size(Duration::ZERO, millis(10), 1000), 1);
+    }
+
+    #[test]
+    fn test_measure_batches_fast_runs() {
+        let config = BenchConfig {
+            warmup: Duration::from_millis(10),
+            budget: Duration::from_secs(10),
+            min_samples: 1,
+            max_samples: 5,
+            sample_time: Duration::from_millis(1),
+        };
+
+        let mut calls = 0u64;
+        let stats = measure(&config, || calls += 1);
+
+        assert_eq!(stats.samples, 5);
+        assert!(calls > 5 * 100, "{} calls", calls);
+        // The duration of a single call, not of a batch
+        assert!(stats.median < Duration::from_micros(10), "{:?}", stats);
+    }
+}
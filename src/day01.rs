@@ -35,9 +35,7 @@ impl Challenge for Day01 {
     }
 
     fn parse(content: &str) -> Result<Self::InputType> {
-        utils::parse_line_separated_list(content)
-            .map(SortedVec::new)
-            .map_err(Into::into)
+        utils::parse_line_separated_list(content).map(SortedVec::new)
     }
 }
 
@@ -64,6 +62,8 @@ impl<T> Deref for SortedVec<T> {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     static NUMBERS: &[u32] = &[1721, 979, 366, 299, 675, 1456];
@@ -83,6 +83,18 @@ mod tests {
             241861950
         );
     }
+
+    proptest! {
+        #[test]
+        fn prop_sorted_vec_is_sorted(numbers: Vec<u32>) {
+            let sorted = SortedVec::new(numbers.clone());
+
+            prop_assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+            let mut expected = numbers;
+            expected.sort_unstable();
+            prop_assert_eq!(&*sorted, &expected);
+        }
+    }
 }
 
 crate::benchmark_challenge!(crate::day01::Day01);
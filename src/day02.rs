@@ -1,9 +1,10 @@
 use std::str::FromStr;
 
 use anyhow::{anyhow, Result};
-use scan_fmt::{scan_fmt, scan_fmt_some};
+use scan_fmt::scan_fmt;
 
-use crate::{utils, Challenge};
+use crate::utils::{self, TokenError};
+use crate::Challenge;
 
 pub struct Day02;
 
@@ -44,15 +45,15 @@ impl PasswordEntry {
     }
 
     pub fn is_valid_positions(&self) -> bool {
+        // The positions start at 1, there is never a letter at position 0
         let is_char_at_pos = |pos: usize| {
-            self.password
-                .chars()
-                .nth(pos)
+            pos.checked_sub(1)
+                .and_then(|idx| self.password.chars().nth(idx))
                 .map(|c| c == self.policy.letter)
                 .unwrap_or(false)
         };
 
-        is_char_at_pos(self.policy.range.0 - 1) ^ is_char_at_pos(self.policy.range.1 - 1)
+        is_char_at_pos(self.policy.range.0) ^ is_char_at_pos(self.policy.range.1)
     }
 }
 
@@ -60,11 +61,22 @@ impl FromStr for PasswordEntry {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (policy, password) = scan_fmt_some!(s, "{[^:]}: {}", PasswordPolicy, String);
-        let policy = policy.ok_or_else(|| anyhow!("Could not parse policy"))?;
-        let password = password.ok_or_else(|| anyhow!("Could not parse password"))?;
-
-        Ok(PasswordEntry { password, policy })
+        let (policy, password) = s
+            .split_once(": ")
+            .ok_or_else(|| anyhow!("Expected \"<policy>: <password>\""))?;
+        let policy = policy
+            .parse()
+            .map_err(|e| TokenError::new(s, policy, anyhow!("Could not parse policy: {}", e)))?;
+        // Drop the `\r` of CRLF line endings and any trailing spaces
+        let password = password.trim();
+        if password.is_empty() {
+            return Err(TokenError::new(s, password, anyhow!("Could not parse password")).into());
+        }
+
+        Ok(PasswordEntry {
+            password: password.to_string(),
+            policy,
+        })
     }
 }
 
@@ -116,6 +128,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_crlf() {
+        let entry: PasswordEntry = "1-3 a: abcde\r".parse().unwrap();
+        assert_eq!(entry.password, "abcde");
+        assert_eq!(
+            Day02::parse("1-3 a: abcde\r\n1-3 b: cdefg\r\n").unwrap(),
+            Day02::parse("1-3 a: abcde\n1-3 b: cdefg\n").unwrap()
+        );
+        assert_eq!("1-3 a: abcde \r\n".parse::<PasswordEntry>().unwrap(), entry);
+    }
+
+    #[test]
+    fn test_parse_error_location() {
+        let error = Day02::parse("1-3 a: abcde\n1-x b: cdefg")
+            .unwrap_err()
+            .downcast::<utils::ParseError>()
+            .unwrap();
+        assert_eq!(error.location.line, 2);
+        assert_eq!(error.location.column, Some(1));
+        assert_eq!(error.width, 5);
+    }
+
+    #[test]
+    fn test_position_zero() {
+        let entry: PasswordEntry = "0-2 a: ba".parse().unwrap();
+        assert!(entry.is_valid_positions());
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(
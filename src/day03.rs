@@ -1,6 +1,7 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use grid::Grid;
 
+use crate::utils::{self, ParseError};
 use crate::Challenge;
 
 pub struct Day03;
@@ -29,11 +30,23 @@ impl Challenge for Day03 {
             .map(|line| line.chars().map(is_tree).collect())
             .collect();
 
-        let width = vec2d[0].len();
-
-        if vec2d.iter().any(|r| r.len() != width) {
-            return Err(anyhow!("All the row doesn't have the same size"));
-        }
+        let width = match vec2d.first() {
+            Some(row) if !row.is_empty() => row.len(),
+            _ => bail!("The grid is empty"),
+        };
+
+        utils::collect_parsed(content.lines().map(|line| {
+            let len = line.chars().count();
+            if len == width {
+                Ok(())
+            } else {
+                Err(ParseError::at(
+                    content,
+                    line,
+                    anyhow!("All the rows must have {} cells, not {}", width, len),
+                ))
+            }
+        }))?;
 
         let vec: Vec<bool> = vec2d.into_iter().flatten().collect();
 
@@ -76,6 +89,7 @@ fn is_tree(c: char) -> bool {
 #[cfg(test)]
 mod tests {
     use super::Day03;
+    use crate::utils;
     use crate::Challenge as _;
 
     const GRID: &str = "..##.......
@@ -96,9 +110,25 @@ mod tests {
 #.#";
         let grid = Day03::parse(grid_str).unwrap();
         assert_eq!(grid.size(), (2, 3));
-        assert_eq!(grid[0][0], false);
-        assert_eq!(grid[0][1], true);
-        assert_eq!(grid[0][2], true);
+        assert!(!grid[0][0]);
+        assert!(grid[0][1]);
+        assert!(grid[0][2]);
+    }
+
+    #[test]
+    fn test_parse_uneven_rows() {
+        let error = Day03::parse(".##\n#.\n...")
+            .unwrap_err()
+            .downcast::<utils::ParseError>()
+            .unwrap();
+        assert_eq!(error.location.line, 2);
+        assert_eq!(error.text, "#.");
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        assert!(Day03::parse("").is_err());
+        assert!(Day03::parse("\n\n").is_err());
     }
 
     #[test]
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use anyhow::{anyhow, Result};
 use itertools::Itertools as _;
 
+use crate::utils::{self, ParseError};
 use crate::Challenge;
 
 pub struct Day04;
@@ -30,18 +31,23 @@ impl Challenge for Day04 {
     }
 
     fn parse(content: &str) -> Result<Self::InputType> {
-        content.split("\n\n").map(parse_entry).collect()
+        utils::collect_parsed(
+            content
+                .split("\n\n")
+                .map(|entry| parse_entry(content, entry)),
+        )
     }
 }
 
-fn parse_entry(entry: &str) -> Result<Passport> {
+/// Parse a passport, which must be a slice of `content` to locate its errors.
+fn parse_entry(content: &str, entry: &str) -> Result<Passport, ParseError> {
     entry
         .split_ascii_whitespace()
         .map(|s| {
             s.split(':')
                 .map(str::to_string)
                 .collect_tuple()
-                .ok_or_else(|| anyhow!("Ill-formated entry: {}", s))
+                .ok_or_else(|| ParseError::at(content, s, anyhow!("Ill-formated entry: {}", s)))
         })
         .collect()
 }
@@ -96,9 +102,7 @@ fn validate_height(s: &str) -> bool {
 fn validate_hair_color(color: &str) -> bool {
     color.starts_with('#')
         && color.len() == 7
-        && (&color[1..])
-            .chars()
-            .all(|c| "0123456789abcdef".contains(c))
+        && color[1..].chars().all(|c| "0123456789abcdef".contains(c))
 }
 
 fn validate_pid(pid: &str) -> bool {
@@ -161,6 +165,17 @@ pid:3556412378 byr:2007";
         assert_eq!(entries[0]["ecl"], "gry");
     }
 
+    #[test]
+    fn test_parse_error_location() {
+        let error = Day04::parse("ecl:gry pid:860033327\n\nbyr:1937 iyr2017")
+            .unwrap_err()
+            .downcast::<ParseError>()
+            .unwrap();
+        assert_eq!(error.location.line, 3);
+        assert_eq!(error.location.column, Some(10));
+        assert_eq!(error.width, 7);
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(Day04::solve1(TEST_LIST1).unwrap(), 2);
@@ -3,6 +3,7 @@ use std::str::FromStr;
 
 use anyhow::{anyhow, Result};
 
+use crate::utils::TokenError;
 use crate::Challenge;
 use itertools::Itertools;
 
@@ -78,20 +79,15 @@ impl FromStr for Ticket {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() != 10 {
+        if s.chars().count() != 10 {
             return Err(anyhow!("The ticket length must be 10: {}", s));
         }
 
-        let row = (&s[..7])
-            .chars()
-            .map(|c| parse_low_high(c, 'F', 'B'))
-            .collect::<Result<Vec<LowHigh>>>()?
+        let (row, column) = s.split_at(s.char_indices().nth(7).map_or(s.len(), |(idx, _)| idx));
+        let row = parse_low_high_list(s, row, 'F', 'B')?
             .as_slice()
             .try_into()?;
-        let column = (&s[7..])
-            .chars()
-            .map(|c| parse_low_high(c, 'L', 'R'))
-            .collect::<Result<Vec<LowHigh>>>()?
+        let column = parse_low_high_list(s, column, 'L', 'R')?
             .as_slice()
             .try_into()?;
 
@@ -99,11 +95,21 @@ impl FromStr for Ticket {
     }
 }
 
+/// Parse the characters of `part`, a slice of the ticket `s`.
+fn parse_low_high_list(s: &str, part: &str, low: char, high: char) -> Result<Vec<LowHigh>> {
+    part.char_indices()
+        .map(|(idx, c)| {
+            parse_low_high(c, low, high)
+                .map_err(|e| TokenError::new(s, &part[idx..idx + c.len_utf8()], e).into())
+        })
+        .collect()
+}
+
 fn parse_low_high(c: char, low: char, high: char) -> Result<LowHigh> {
     match c {
         _ if c == low => Ok(LowHigh::Low),
         _ if c == high => Ok(LowHigh::High),
-        _ => Err(anyhow!("Must be {} or {}, not {}", 'F', 'B', c)),
+        _ => Err(anyhow!("Must be {} or {}, not {}", low, high, c)),
     }
 }
 
@@ -115,6 +121,8 @@ pub enum LowHigh {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -132,6 +140,17 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_parse_error_location() {
+        let error = Day05::parse("FBFBFFBRLR\nFBFBFFBRXR")
+            .unwrap_err()
+            .downcast::<crate::utils::ParseError>()
+            .unwrap();
+        assert_eq!(error.location.line, 2);
+        assert_eq!(error.location.column, Some(9));
+        assert_eq!(error.to_string(), "line 2, column 9: Must be L or R, not X");
+    }
+
     #[test]
     fn test_compute_id() {
         let ticket: Ticket = "BFFFBBFRRR".parse().unwrap();
@@ -140,6 +159,12 @@ mod tests {
         assert_eq!(ticket.get_id(), 567);
     }
 
+    #[test]
+    fn test_parse_multibyte() {
+        assert!("FFFFFFéLR".parse::<Ticket>().is_err());
+        assert!("FFFFFFFé\u{301}R".parse::<Ticket>().is_err());
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(
@@ -147,6 +172,20 @@ mod tests {
             820
         );
     }
+
+    proptest! {
+        #[test]
+        fn prop_ticket_id_is_binary(s in "[FB]{7}[LR]{3}") {
+            let ticket: Ticket = s.parse().unwrap();
+            let binary: String = s
+                .chars()
+                .map(|c| if c == 'B' || c == 'R' { '1' } else { '0' })
+                .collect();
+
+            prop_assert!(ticket.get_id() <= 1023);
+            prop_assert_eq!(ticket.get_id(), u32::from_str_radix(&binary, 2).unwrap());
+        }
+    }
 }
 
 crate::benchmark_challenge!(crate::day05::Day05);
@@ -1,5 +1,6 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
+use crate::utils::{self, ParseError};
 use crate::Challenge;
 
 pub struct Day06;
@@ -25,18 +26,35 @@ impl Challenge for Day06 {
     }
 
     fn parse(content: &str) -> Result<Self::InputType> {
-        Ok(content
-            .split("\n\n")
-            .map(|group| {
-                group
-                    .lines()
-                    .map(|person| person.bytes().fold(0, |b, c| b | (1 << (c - b'a'))))
-                    .collect()
-            })
+        let groups = utils::collect_parsed(content.split("\n\n").map(|group| {
+            group
+                .lines()
+                .map(|person| parse_person(content, person))
+                .collect::<Result<Vec<_>, _>>()
+        }))?;
+
+        // Blank lines in excess make empty groups
+        Ok(groups
+            .into_iter()
+            .filter(|group| !group.is_empty())
             .collect())
     }
 }
 
+/// Parse the answers of a person, a line of `content`, as a set of bits.
+fn parse_person(content: &str, person: &str) -> Result<u32, ParseError> {
+    person
+        .char_indices()
+        .try_fold(0, |answers, (idx, c)| match c {
+            'a'..='z' => Ok(answers | 1 << (c as u8 - b'a')),
+            _ => Err(ParseError::at(
+                content,
+                &person[idx..idx + c.len_utf8()],
+                anyhow!("Expected a letter from a to z, not {:?}", c),
+            )),
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,6 +91,18 @@ a
 
 b";
 
+    #[test]
+    fn test_parse_malformed() {
+        let error = Day06::parse("ab\n\naB")
+            .unwrap_err()
+            .downcast::<ParseError>()
+            .unwrap();
+        assert_eq!(error.location.line, 3);
+        assert_eq!(error.location.column, Some(2));
+
+        assert_eq!(Day06::parse("\n\n\n\nab\n\n").unwrap(), vec![vec![0b11]]);
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(Day06::solve1(EXAMPLE).unwrap(), 11);
@@ -4,6 +4,7 @@ use anyhow::{anyhow, Result};
 use itertools::Itertools as _;
 use petgraph::graph::{DiGraph, NodeIndex};
 
+use crate::utils::{self, ParseError};
 use crate::Challenge;
 
 pub struct Day07;
@@ -39,10 +40,7 @@ impl Challenge for Day07 {
     }
 
     fn parse(content: &str) -> Result<Self::InputType> {
-        let rules_v = content
-            .lines()
-            .map(parse_rule)
-            .collect::<Result<Vec<_>>>()?;
+        let rules_v = utils::collect_parsed(content.lines().map(|line| parse_rule(content, line)))?;
 
         let mut rules = Rules::new();
         let mut indexes = HashMap::new();
@@ -85,11 +83,12 @@ fn get_number_of_bags(
         + 1
 }
 
-fn parse_rule(rule: &str) -> Result<(String, Vec<(String, usize)>)> {
+/// Parse a rule, which must be a slice of `content` to locate its errors.
+fn parse_rule(content: &str, rule: &str) -> Result<(String, Vec<(String, usize)>), ParseError> {
     let (name, contained) = rule
         .split(" bags contain ")
         .collect_tuple()
-        .ok_or_else(|| anyhow!("could not parse rule"))?;
+        .ok_or_else(|| ParseError::at(content, rule, anyhow!("could not parse rule")))?;
 
     let contained = if contained == "no other bags." {
         Vec::new()
@@ -97,11 +96,12 @@ fn parse_rule(rule: &str) -> Result<(String, Vec<(String, usize)>)> {
         contained
             .split(", ")
             .map(|c| {
-                let (n_str, name) = c
-                    .splitn(2, ' ')
-                    .collect_tuple()
-                    .ok_or_else(|| anyhow!("could not parse rule"))?;
-                let n = n_str.parse()?;
+                let (n_str, name) = c.splitn(2, ' ').collect_tuple().ok_or_else(|| {
+                    ParseError::at(content, c, anyhow!("could not parse contained bag"))
+                })?;
+                let n = n_str
+                    .parse()
+                    .map_err(|e| ParseError::at(content, n_str, e))?;
                 Ok((
                     name.trim_end_matches('.')
                         .trim_end_matches(" bags")
@@ -110,7 +110,7 @@ fn parse_rule(rule: &str) -> Result<(String, Vec<(String, usize)>)> {
                     n,
                 ))
             })
-            .collect::<Result<Vec<(String, usize)>>>()?
+            .collect::<Result<Vec<(String, usize)>, ParseError>>()?
     };
 
     Ok((name.to_owned(), contained))
@@ -153,6 +153,20 @@ dark violet bags contain no other bags.";
         );
     }
 
+    #[test]
+    fn test_parse_error_location() {
+        let error = Day07::parse(
+            "faded blue bags contain no other bags.
+light red bags contain 1 bright white bag, two muted yellow bags.",
+        )
+        .unwrap_err()
+        .downcast::<ParseError>()
+        .unwrap();
+        assert_eq!(error.location.line, 2);
+        assert_eq!(error.location.column, Some(44));
+        assert_eq!(error.width, 3);
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(Day07::solve1(EXAMPLE1).unwrap(), 4);
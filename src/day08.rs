@@ -3,6 +3,7 @@ use std::str::FromStr;
 
 use anyhow::{anyhow, Result};
 
+use crate::utils::TokenError;
 use crate::Challenge;
 use itertools::Itertools;
 
@@ -28,15 +29,11 @@ impl Challenge for Day08 {
 
     // Naive brute-force solution
     fn part2(input: &Self::InputType) -> Result<Self::OutputType> {
-        for (instruction_idx, _) in
-            input
-                .instructions
-                .iter()
-                .enumerate()
-                .filter(|(_, i)| match i.0 {
-                    OpCode::Nop | OpCode::Jmp => true,
-                    _ => false,
-                })
+        for (instruction_idx, _) in input
+            .instructions
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| matches!(i.0, OpCode::Nop | OpCode::Jmp))
         {
             if let Ok(fixed_ae) = try_fix(input, instruction_idx) {
                 return Ok(fixed_ae.acc());
@@ -141,7 +138,10 @@ impl FromStr for Instruction {
             .split(' ')
             .collect_tuple()
             .ok_or_else(|| anyhow!("Could not parse instruction"))?;
-        Ok(Self(opcode.parse()?, param.parse()?))
+        let opcode = opcode.parse().map_err(|e| TokenError::new(s, opcode, e))?;
+        let param = param.parse().map_err(|e| TokenError::new(s, param, e))?;
+
+        Ok(Self(opcode, param))
     }
 }
 
@@ -186,6 +186,17 @@ acc +6";
         assert_eq!(ae.instructions()[0], Instruction(OpCode::Nop, 0));
     }
 
+    #[test]
+    fn test_parse_error_location() {
+        let error = Day08::parse("nop +0\nacc +1\njpm +4")
+            .unwrap_err()
+            .downcast::<crate::utils::ParseError>()
+            .unwrap();
+        assert_eq!(error.location.line, 3);
+        assert_eq!(error.location.column, Some(1));
+        assert_eq!(error.width, 3);
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(Day08::solve1(EXAMPLE).unwrap(), 5);
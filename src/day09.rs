@@ -39,7 +39,7 @@ impl Challenge for Day09 {
     }
 
     fn parse(content: &str) -> Result<Self::InputType> {
-        crate::utils::parse_line_separated_list(content).map_err(Into::into)
+        crate::utils::parse_line_separated_list(content)
     }
 }
 
@@ -56,13 +56,13 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let numbers = (1..=25).chain(vec![26, 49, 100, 50].into_iter()).collect();
+        let numbers = (1..=25).chain(vec![26, 49, 100, 50]).collect();
         assert_eq!(Day09::part1(&numbers).unwrap(), 100);
     }
 
     #[test]
     fn test_part2() {
-        let numbers = (1..=25).chain(vec![26, 49, 100, 50].into_iter()).collect();
+        let numbers = (1..=25).chain(vec![26, 49, 100, 50]).collect();
         assert_eq!(Day09::part2(&numbers).unwrap(), 9 + 16);
     }
 }
@@ -1,6 +1,6 @@
 use anyhow::Result;
 
-use crate::Challenge;
+use crate::{Challenge, Variant};
 use itertools::Itertools;
 
 pub struct Day10;
@@ -41,7 +41,15 @@ impl Challenge for Day10 {
     }
 
     fn parse(content: &str) -> Result<Self::InputType> {
-        crate::utils::parse_line_separated_list(content).map_err(Into::into)
+        crate::utils::parse_line_separated_list(content)
+    }
+
+    fn variants() -> Vec<Variant<Self>> {
+        vec![Variant {
+            part: 2,
+            name: "telly",
+            solve: |input| Ok(part2_telly(&prepare_input(input))),
+        }]
     }
 }
 
@@ -73,11 +81,9 @@ fn part2_backtrack(input: &[u64]) -> u64 {
     combination_per_adapter[0]
 }
 
-#[allow(unused)]
 const SUITE: &[u64] = &[1, 1, 2, 4, 7, 11, 16, 22];
 
 // From Telly
-#[allow(unused)]
 fn part2_telly(input: &[u64]) -> u64 {
     // Diffs
     input
@@ -101,6 +107,8 @@ fn is_suitable_for(socket: u64, adapter: u64) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     const EXAMPLE1: &str = "16
@@ -178,6 +186,27 @@ mod tests {
             19208
         );
     }
+
+    /// Sorted adapters starting at 0, with differences of 1 or 3 and at most four 1s in a row,
+    /// where the sequence of Telly is exact.
+    fn adapters() -> impl Strategy<Value = Vec<u64>> {
+        prop::collection::vec(0..=4usize, 1..30).prop_map(|runs| {
+            let mut adapters = vec![0];
+            for run in runs {
+                for diff in std::iter::repeat_n(1, run).chain(Some(3)) {
+                    adapters.push(adapters.last().unwrap() + diff);
+                }
+            }
+            adapters
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_variants_agree(adapters in adapters()) {
+            prop_assert_eq!(part2_backtrack(&adapters), part2_telly(&adapters));
+        }
+    }
 }
 
 crate::benchmark_challenge!(crate::day10::Day10);
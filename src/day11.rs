@@ -3,6 +3,7 @@ use std::convert::TryFrom;
 use anyhow::{anyhow, Result};
 use grid::Grid;
 
+use crate::utils::{self, ParseError};
 use crate::Challenge;
 use itertools::Itertools;
 
@@ -33,12 +34,24 @@ impl Challenge for Day11 {
             .ok_or_else(|| anyhow!("Empty grid"))?
             .trim_end()
             .len();
-        let cells = content
-            .trim()
-            .chars()
-            .filter(|c| !c.is_whitespace())
-            .map(<Cell as TryFrom<char>>::try_from)
-            .collect::<Result<_>>()?;
+        let cells = utils::collect_parsed(content.trim().lines().flat_map(|line| {
+            let line = line.trim_end();
+            let row_error = (line.len() != width).then(|| {
+                ParseError::at(
+                    content,
+                    line,
+                    anyhow!("All the rows must have {} cells, not {}", width, line.len()),
+                )
+            });
+
+            row_error
+                .into_iter()
+                .map(Err)
+                .chain(line.char_indices().map(move |(idx, c)| {
+                    Cell::try_from(c)
+                        .map_err(|e| ParseError::at(content, &line[idx..idx + c.len_utf8()], e))
+                }))
+        }))?;
 
         Ok(Grid::from_vec(cells, width))
     }
@@ -187,6 +200,17 @@ LLLLLLLLLL
 L.LLLLLL.L
 L.LLLLL.LL";
 
+    #[test]
+    fn test_parse_error_location() {
+        let error = Day11::parse("L.L\nLXL")
+            .unwrap_err()
+            .downcast::<ParseError>()
+            .unwrap();
+        assert_eq!(error.location.line, 2);
+        assert_eq!(error.location.column, Some(2));
+        assert_eq!(error.to_string(), "line 2, column 2: Cannot parse seat: X");
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(Day11::solve1(EXAMPLE).unwrap(), 37);
@@ -3,6 +3,7 @@ use std::convert::TryFrom;
 use anyhow::{anyhow, Result};
 
 use crate::day12::InstructionType::Rotate;
+use crate::utils::TokenError;
 use crate::Challenge;
 
 pub struct Day12;
@@ -18,7 +19,7 @@ impl Challenge for Day12 {
 
         input.iter().for_each(|i| i.apply_ship(&mut state));
 
-        Ok(state.coords.0.abs() as u32 + state.coords.1.abs() as u32)
+        Ok(state.coords.0.unsigned_abs() + state.coords.1.unsigned_abs())
     }
 
     fn part2(input: &Self::InputType) -> Result<Self::OutputType> {
@@ -29,7 +30,7 @@ impl Challenge for Day12 {
             .iter()
             .for_each(|i| i.apply_waypoint(&mut state, &mut waypoint));
 
-        Ok(state.coords.0.abs() as u32 + state.coords.1.abs() as u32)
+        Ok(state.coords.0.unsigned_abs() + state.coords.1.unsigned_abs())
     }
 
     fn parse(content: &str) -> Result<Self::InputType> {
@@ -121,12 +122,27 @@ impl std::str::FromStr for Instruction {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let typ = InstructionType::try_from(
-            s.chars()
-                .next()
-                .ok_or_else(|| anyhow!("Empty instruction"))?,
-        )?;
-        let amount = s[1..].parse()?;
+        let first = s
+            .chars()
+            .next()
+            .ok_or_else(|| anyhow!("Empty instruction"))?;
+        let typ = InstructionType::try_from(first)
+            .map_err(|e| TokenError::new(s, &s[..first.len_utf8()], e))?;
+        let amount_str = &s[first.len_utf8()..];
+        let amount = amount_str
+            .parse()
+            .map_err(|e| TokenError::new(s, amount_str, e))?;
+        if matches!(typ, Rotate(_)) && amount % 90 != 0 {
+            return Err(TokenError::new(
+                s,
+                amount_str,
+                anyhow!(
+                    "The rotations must be multiples of 90 degrees, not {}",
+                    amount
+                ),
+            )
+            .into());
+        }
 
         Ok(Self { typ, amount })
     }
@@ -229,7 +245,7 @@ impl Rotation {
 /// ```
 fn normalize_rotation(rotation: Rotation, amount: i32) -> (Rotation, u32) {
     let (rotation, amount) = if amount < 0 {
-        (rotation.opposite(), amount.abs() as u32)
+        (rotation.opposite(), amount.unsigned_abs())
     } else {
         (rotation, amount as u32)
     };
@@ -245,6 +261,8 @@ fn normalize_rotation(rotation: Rotation, amount: i32) -> (Rotation, u32) {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     const EXAMPLE: &str = "F10
@@ -253,6 +271,25 @@ F7
 R90
 F11";
 
+    #[test]
+    fn test_parse_error_location() {
+        let error = Day12::parse("F10\nN3\nR9O")
+            .unwrap_err()
+            .downcast::<crate::utils::ParseError>()
+            .unwrap();
+        assert_eq!(error.location.line, 3);
+        assert_eq!(error.location.column, Some(2));
+        assert_eq!(error.width, 2);
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        assert!(Day12::parse("Fé").is_err());
+        assert!(Day12::parse("éF").is_err());
+        assert!(Day12::parse("R45").is_err());
+        assert!(Day12::parse("L-270").is_ok());
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(Day12::solve1(EXAMPLE).unwrap(), 25);
@@ -260,7 +297,32 @@ F11";
 
     #[test]
     fn test_part2() {
-        assert_eq!(Day12::solve1(EXAMPLE).unwrap(), 286);
+        assert_eq!(Day12::solve2(EXAMPLE).unwrap(), 286);
+    }
+
+    fn rotation() -> impl Strategy<Value = Rotation> {
+        prop_oneof![Just(Rotation::Left), Just(Rotation::Right)]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_four_rotations_are_identity(
+            rotation in rotation(),
+            x in -1000..1000,
+            y in -1000..1000,
+        ) {
+            let mut waypoint = Waypoint(x, y);
+            for _ in 0..4 {
+                waypoint.rotate(rotation, 90);
+            }
+            prop_assert_eq!(waypoint, Waypoint(x, y));
+        }
+
+        #[test]
+        fn prop_normalize_rotation_is_idempotent(rotation in rotation(), amount: i32) {
+            let (rotation, amount) = normalize_rotation(rotation, amount);
+            prop_assert_eq!(normalize_rotation(rotation, amount as i32), (rotation, amount));
+        }
     }
 }
 
@@ -1,4 +1,4 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 
 use crate::Challenge;
 
@@ -8,17 +8,17 @@ impl Challenge for DayXX {
     const DAY_NUMBER: u32 = XX;
 
     type InputType = ();
-    type OutputType = ();
+    type OutputType = u64;
 
-    fn part1(input: &Self::InputType) -> Result<Self::OutputType> {
+    fn part1(_input: &Self::InputType) -> Result<Self::OutputType> {
         unimplemented!()
     }
 
-    fn part2(input: &Self::InputType) -> Result<Self::OutputType> {
+    fn part2(_input: &Self::InputType) -> Result<Self::OutputType> {
         unimplemented!()
     }
 
-    fn parse(content: &str) -> Result<Self::InputType> {
+    fn parse(_content: &str) -> Result<Self::InputType> {
         unimplemented!()
     }
 }
@@ -0,0 +1,243 @@
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::registry::DAYS;
+use crate::utils::{ParseError, ParseErrors};
+
+/// A position in an input, starting at 1.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Location {
+    pub line: usize,
+    pub column: Option<usize>,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "line {}, column {}", self.line, column),
+            None => write!(f, "line {}", self.line),
+        }
+    }
+}
+
+/// The ways running a challenge can fail.
+///
+/// The sources are shared so the same failure can be reported for several parts.
+#[derive(Clone, Debug)]
+pub enum Error {
+    /// The day is not in the calendar.
+    DayOutOfRange(u32),
+    /// The day is in the calendar, but has no solver.
+    DayNotImplemented(u32),
+    /// The year has no event in the registry.
+    YearNotImplemented(u32),
+    /// The part is not 1 or 2.
+    InvalidPart(u8),
+    /// The part has no implementation with this name.
+    UnknownVariant {
+        day: u32,
+        part: u8,
+        name: String,
+        available: Vec<&'static str>,
+    },
+    /// The input could not be read.
+    InputUnavailable {
+        path: String,
+        source: Arc<io::Error>,
+    },
+    /// The input could not be parsed by the challenge.
+    Parse {
+        day: u32,
+        location: Option<Location>,
+        source: Arc<anyhow::Error>,
+    },
+    /// The challenge failed to solve a part.
+    Solve {
+        day: u32,
+        part: u8,
+        source: Arc<anyhow::Error>,
+    },
+    /// The isolated part did not finish in time.
+    Timeout { day: u32, part: u8, limit: Duration },
+    /// The isolated part exceeded its memory limit.
+    OutOfMemory { day: u32, part: u8 },
+    /// The isolated part panicked.
+    Panic { day: u32, part: u8, message: String },
+    /// The isolated part was killed by a signal.
+    Signal { day: u32, part: u8, signal: i32 },
+    /// The isolated part failed without reporting why.
+    Child { day: u32, part: u8, message: String },
+}
+
+impl Error {
+    pub(crate) fn input_unavailable(path: impl Into<String>, source: io::Error) -> Self {
+        Error::InputUnavailable {
+            path: path.into(),
+            source: Arc::new(source),
+        }
+    }
+
+    pub(crate) fn parse(day: u32, source: anyhow::Error) -> Self {
+        let location = source
+            .downcast_ref::<ParseError>()
+            .or_else(|| source.downcast_ref::<ParseErrors>().map(|e| &e.0[0]))
+            .map(|e| e.location);
+
+        Error::Parse {
+            day,
+            location,
+            source: Arc::new(source),
+        }
+    }
+
+    pub(crate) fn solve(day: u32, part: u8, source: anyhow::Error) -> Self {
+        Error::Solve {
+            day,
+            part,
+            source: Arc::new(source),
+        }
+    }
+
+    /// The kind of failure, as shown in the status of a summary.
+    pub fn status(&self) -> &'static str {
+        match self {
+            Error::Timeout { .. } => "timeout",
+            Error::OutOfMemory { .. } => "out of memory",
+            Error::Panic { .. } => "panic",
+            Error::Signal { .. } => "signal",
+            _ => "error",
+        }
+    }
+
+    /// Render the parse errors like compiler diagnostics, if they are located.
+    ///
+    /// `origin` is the name of the input, as in [`InputProvider::describe`](crate::InputProvider::describe).
+    pub fn diagnostics(&self, origin: &str) -> Option<String> {
+        match self {
+            Error::Parse { source, .. } => source
+                .downcast_ref::<ParseError>()
+                .map(|e| e.render(origin))
+                .or_else(|| {
+                    source
+                        .downcast_ref::<ParseErrors>()
+                        .map(|e| e.render(origin))
+                }),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::DayOutOfRange(day) => write!(
+                f,
+                "day {} out of range ({}-{})",
+                day,
+                DAYS.start(),
+                DAYS.end()
+            ),
+            Error::DayNotImplemented(day) => write!(f, "day {} not implemented", day),
+            Error::YearNotImplemented(year) => write!(f, "year {} not implemented", year),
+            Error::InvalidPart(part) => write!(f, "part must be 1 or 2, not {}", part),
+            Error::UnknownVariant {
+                day,
+                part,
+                name,
+                available,
+            } => write!(
+                f,
+                "day {} part {} has no variant {} (available: {})",
+                day,
+                part,
+                name,
+                available.join(", ")
+            ),
+            Error::InputUnavailable { path, source } => {
+                write!(f, "cannot read {}: {}", path, source)
+            }
+            Error::Parse { day, source, .. } => {
+                write!(f, "cannot parse the input of day {}: {:#}", day, source)
+            }
+            Error::Solve { source, .. } => write!(f, "{:#}", source),
+            Error::Timeout { day, part, limit } => {
+                write!(f, "day {} part {} did not finish in {:?}", day, part, limit)
+            }
+            Error::OutOfMemory { day, part } => {
+                write!(f, "day {} part {} ran out of memory", day, part)
+            }
+            Error::Panic { day, part, message } => {
+                write!(f, "day {} part {} panicked: {}", day, part, message)
+            }
+            Error::Signal { day, part, signal } => {
+                write!(
+                    f,
+                    "day {} part {} was killed by signal {}",
+                    day, part, signal
+                )
+            }
+            Error::Child { day, part, message } => {
+                write!(f, "day {} part {} failed: {}", day, part, message)
+            }
+        }
+    }
+}
+
+// The sources are part of the messages, so they are not exposed again by `source()`.
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Error::DayOutOfRange(26).to_string(),
+            "day 26 out of range (1-25)"
+        );
+        assert_eq!(
+            Error::input_unavailable("inputs/13.txt", io::ErrorKind::NotFound.into()).to_string(),
+            "cannot read inputs/13.txt: entity not found"
+        );
+        assert_eq!(
+            Error::UnknownVariant {
+                day: 10,
+                part: 1,
+                name: "telly".to_string(),
+                available: vec!["default"],
+            }
+            .to_string(),
+            "day 10 part 1 has no variant telly (available: default)"
+        );
+        assert_eq!(
+            Error::solve(1, 2, anyhow::anyhow!("no combination")).to_string(),
+            "no combination"
+        );
+    }
+
+    #[test]
+    fn test_parse_location() {
+        let error = crate::utils::parse_line_separated_list::<u32>("1\n2\nthree")
+            .map_err(|e| Error::parse(1, e))
+            .unwrap_err();
+
+        match error {
+            Error::Parse { day, location, .. } => {
+                assert_eq!(day, 1);
+                assert_eq!(
+                    location,
+                    Some(Location {
+                        line: 3,
+                        column: Some(1)
+                    })
+                );
+            }
+            _ => panic!("Expected a parse error, got {:?}", error),
+        }
+    }
+}
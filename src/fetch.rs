@@ -0,0 +1,297 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context as _, Result};
+
+use crate::registry::DAYS;
+use crate::{EnvDirectory, Error};
+
+/// The site serving the puzzle inputs.
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+/// The environment variable overriding [`DEFAULT_BASE_URL`], e.g. to use a mock server.
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+/// The environment variable holding the session token.
+pub const SESSION_ENV: &str = "AOC_SESSION";
+/// The file holding the session token when [`SESSION_ENV`] is not set.
+pub const DEFAULT_SESSION_PATH: &str = ".aoc-session";
+
+pub(crate) const USER_AGENT: &str =
+    concat!("advent2020/", env!("CARGO_PKG_VERSION"), " (input fetcher)");
+
+/// Downloads the inputs of the days of a year, and caches them as `NN.txt` files.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Fetcher {
+    pub base_url: String,
+    /// The value of the `session` cookie of a logged-in user.
+    pub session: String,
+    /// The year of the event of the inputs.
+    pub year: u32,
+    /// Where the inputs are cached.
+    pub inputs_dir: PathBuf,
+}
+
+/// Where a fetched input comes from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Fetched {
+    /// The input was already cached, and not downloaded again.
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+impl Fetched {
+    pub fn path(&self) -> &Path {
+        match self {
+            Fetched::Cached(path) | Fetched::Downloaded(path) => path,
+        }
+    }
+}
+
+impl Fetcher {
+    /// A fetcher caching the inputs in the directory of the year of the default inputs, see
+    /// [`EnvDirectory`].
+    pub fn new(session: String, year: u32) -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session,
+            year,
+            inputs_dir: EnvDirectory::default().directory().year_dir(year),
+        }
+    }
+
+    /// The file caching the input of a day.
+    pub fn cache_path(&self, day: u32) -> PathBuf {
+        self.inputs_dir.join(format!("{:02}.txt", day))
+    }
+
+    /// Download the input of a day, unless it is already cached, see [`is_cached`].
+    pub fn fetch(&self, day: u32) -> Result<Fetched> {
+        if !DAYS.contains(&day) {
+            return Err(Error::DayOutOfRange(day).into());
+        }
+
+        let path = self.cache_path(day);
+        if is_cached(&path) {
+            return Ok(Fetched::Cached(path));
+        }
+
+        let content = self.download(day)?;
+        fs::create_dir_all(&self.inputs_dir)
+            .with_context(|| format!("while creating {}", self.inputs_dir.display()))?;
+        // Written aside first, so that an interrupted write is not taken for the input
+        let partial = path.with_extension("txt.part");
+        fs::write(&partial, content)
+            .and_then(|()| fs::rename(&partial, &path))
+            .with_context(|| format!("while writing {}", path.display()))?;
+
+        Ok(Fetched::Downloaded(path))
+    }
+
+    fn url(&self, day: u32) -> String {
+        format!(
+            "{}/{}/day/{}/input",
+            self.base_url.trim_end_matches('/'),
+            self.year,
+            day
+        )
+    }
+
+    fn download(&self, day: u32) -> Result<String> {
+        let url = self.url(day);
+        let response = ureq::get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .call();
+
+        match response {
+            Ok(response) => {
+                let content = response
+                    .into_string()
+                    .with_context(|| format!("while downloading {}", url))?;
+                if content.is_empty() {
+                    bail!("{} returned an empty input", url);
+                }
+                Ok(content)
+            }
+            Err(ureq::Error::Status(404, _)) => {
+                bail!("The input of day {} is not available yet ({})", day, url)
+            }
+            // The site answers 400 or 500 rather than 401 to an invalid session
+            Err(ureq::Error::Status(code @ (400 | 401 | 403 | 500), _)) => {
+                bail!("{} answered {}, is the session token valid?", url, code)
+            }
+            Err(ureq::Error::Status(code, _)) => bail!("{} answered {}", url, code),
+            Err(error) => Err(error).with_context(|| format!("while downloading {}", url)),
+        }
+    }
+}
+
+/// Is there an input in the file? An empty file, such as the ones created by
+/// [`new_day`](crate::new_day), is not one.
+pub fn is_cached(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| metadata.len() > 0)
+}
+
+/// The session token, from the [`SESSION_ENV`] environment variable or the file at `path`.
+pub fn session_token(path: &Path) -> Result<String> {
+    session_token_from(env::var(SESSION_ENV).ok(), path)
+}
+
+fn session_token_from(var: Option<String>, path: &Path) -> Result<String> {
+    if let Some(token) = var.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        return Ok(token.to_string());
+    }
+
+    match fs::read_to_string(path) {
+        Ok(token) if !token.trim().is_empty() => Ok(token.trim().to_string()),
+        Ok(_) => bail!("The session token file {} is empty", path.display()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => bail!(
+            "No session token: set {} or write the session cookie to {}",
+            SESSION_ENV,
+            path.display()
+        ),
+        Err(error) => Err(error).with_context(|| format!("while reading {}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    /// Serve the responses to as many connections, and return the received requests.
+    fn serve(responses: Vec<(u16, &'static str)>) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|(status, body)| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut request = Vec::new();
+                    let mut buffer = [0; 1024];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        let n = stream.read(&mut buffer).unwrap();
+                        request.extend_from_slice(&buffer[..n]);
+                    }
+                    write!(
+                        stream,
+                        "HTTP/1.1 {} Whatever\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    )
+                    .unwrap();
+                    String::from_utf8(request).unwrap()
+                })
+                .collect()
+        });
+
+        (base_url, server)
+    }
+
+    fn fetcher(base_url: String, name: &str) -> Fetcher {
+        let inputs_dir =
+            env::temp_dir().join(format!("advent2020-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&inputs_dir);
+
+        Fetcher {
+            base_url,
+            session: "s3cr3t".to_string(),
+            year: 2020,
+            inputs_dir,
+        }
+    }
+
+    #[test]
+    fn test_fetch_then_cache() {
+        let (base_url, server) = serve(vec![(200, "1\n2\n")]);
+        let fetcher = fetcher(base_url, "fetch");
+
+        let path = fetcher.cache_path(13);
+        assert_eq!(
+            fetcher.fetch(13).unwrap(),
+            Fetched::Downloaded(path.clone())
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1\n2\n");
+        // Not downloaded again: the server only answers once
+        assert_eq!(fetcher.fetch(13).unwrap(), Fetched::Cached(path));
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2020/day/13/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("session=s3cr3t"));
+
+        fs::remove_dir_all(&fetcher.inputs_dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_after_new_day() {
+        let (base_url, server) = serve(vec![(200, "1\n2\n")]);
+        let mut fetcher = fetcher(base_url, "fetch-new-day");
+        let root = fetcher.inputs_dir.clone();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("src").join("lib.rs"),
+            "mod day01;\n\nstatic CHALLENGES: &[(u32, &Solver)] = &[\n    (1, &ChallengeImpl(day01::Day01)),\n];\n",
+        )
+        .unwrap();
+        fetcher.inputs_dir = root.join("inputs");
+
+        // The input created empty by new-day is downloaded
+        crate::new_day(&root, 13).unwrap();
+        let path = fetcher.cache_path(13);
+        assert!(path.exists() && !is_cached(&path));
+        assert_eq!(
+            fetcher.fetch(13).unwrap(),
+            Fetched::Downloaded(path.clone())
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1\n2\n");
+        assert!(is_cached(&path));
+
+        server.join().unwrap();
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_errors() {
+        let (base_url, server) = serve(vec![(404, "Not found"), (400, "Log in")]);
+        let fetcher = fetcher(base_url, "fetch-errors");
+
+        let error = fetcher.fetch(14).unwrap_err();
+        assert!(error.to_string().contains("not available yet"));
+        let error = fetcher.fetch(14).unwrap_err();
+        assert!(error.to_string().contains("session token"));
+        assert!(!fetcher.cache_path(14).exists());
+        let error = fetcher.fetch(26).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::DayOutOfRange(26))
+        ));
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_session_token() {
+        let path = env::temp_dir().join(format!("advent2020-session-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        assert!(session_token_from(None, &path).is_err());
+        fs::write(&path, "from-file\n").unwrap();
+        assert_eq!(session_token_from(None, &path).unwrap(), "from-file");
+        assert_eq!(
+            session_token_from(Some("from-env".to_string()), &path).unwrap(),
+            "from-env"
+        );
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,44 @@
+//! Entry points for the fuzz targets of `fuzz/`, which cannot reach the private parsers.
+//!
+//! The results are dropped: the targets only check that parsing never panics.
+
+use std::str::FromStr;
+
+use crate::registry::Registry;
+use crate::{day02, day05, day08, day12, with_all_parse_errors};
+
+/// Parse the input of a day of a year with its `Challenge::parse`, stopping at the first error
+/// and collecting all of them.
+///
+/// # Panics
+///
+/// If the day is not implemented.
+pub fn parse_day(year: u32, day: u32, content: &str) {
+    let solver = Registry::global().get(year, day).expect("Unknown day");
+    let _ = solver.parse(content);
+    let _ = with_all_parse_errors(|| solver.parse(content));
+}
+
+pub fn parse_password_entry(s: &str) {
+    parse::<day02::PasswordEntry>(s);
+}
+
+pub fn parse_ticket(s: &str) {
+    parse::<day05::Ticket>(s);
+}
+
+pub fn parse_assembly_emulator(s: &str) {
+    parse::<day08::AssemblyEmulator>(s);
+}
+
+pub fn parse_assembly_instruction(s: &str) {
+    parse::<day08::Instruction>(s);
+}
+
+pub fn parse_navigation_instruction(s: &str) {
+    parse::<day12::Instruction>(s);
+}
+
+fn parse<T: FromStr>(s: &str) {
+    let _ = s.parse::<T>();
+}
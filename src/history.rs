@@ -0,0 +1,443 @@
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::runner::part_label;
+use crate::{table, DayBench};
+
+/// The default location of the benchmark history, relative to the working directory.
+pub const DEFAULT_HISTORY_PATH: &str = "bench-history.jsonl";
+
+/// How many standard errors a change of the median must exceed to not be noise.
+const NOISE_SIGMAS: f64 = 3.0;
+
+/// The benchmark of a step of a day at some revision, a line of the history file.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct HistoryEntry {
+    pub day: u32,
+    /// `parse`, `part1` or `part2`.
+    pub step: String,
+    /// The variant of the part, `None` for the parsing.
+    pub variant: Option<String>,
+    pub input: String,
+    /// The output of `git describe --always --dirty`, if available.
+    pub revision: Option<String>,
+    /// When the benchmark ran, in RFC 3339.
+    pub timestamp: String,
+    pub samples: usize,
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub mean_ns: u64,
+    pub p95_ns: u64,
+    pub stddev_ns: u64,
+}
+
+impl HistoryEntry {
+    /// The entries of the steps of a benchmark, without the failed parts.
+    pub fn from_bench(
+        input: &str,
+        bench: &DayBench,
+        revision: Option<&str>,
+        timestamp: DateTime<Utc>,
+    ) -> Vec<Self> {
+        let timestamp = timestamp.to_rfc3339_opts(SecondsFormat::Secs, true);
+        let parts = bench.parts.iter().filter_map(|part| {
+            let step = if part.part == 1 { "part1" } else { "part2" };
+            Some((step, Some(part.variant.clone()), part.stats.as_ref().ok()?))
+        });
+
+        std::iter::once(("parse", None, &bench.parse))
+            .chain(parts)
+            .map(|(step, variant, stats)| {
+                let nanos = |d: Duration| d.as_nanos() as u64;
+                Self {
+                    day: bench.day,
+                    step: step.to_string(),
+                    variant,
+                    input: input.to_string(),
+                    revision: revision.map(str::to_string),
+                    timestamp: timestamp.clone(),
+                    samples: stats.samples,
+                    min_ns: nanos(stats.min),
+                    median_ns: nanos(stats.median),
+                    mean_ns: nanos(stats.mean),
+                    p95_ns: nanos(stats.p95),
+                    stddev_ns: nanos(stats.stddev),
+                }
+            })
+            .collect()
+    }
+
+    /// The name of the step, such as `Parse` or `Part 2 (telly)`.
+    pub fn name(&self) -> String {
+        match (self.step.strip_prefix("part"), &self.variant) {
+            (Some(part), Some(variant)) => {
+                format!("Part {}", part_label(part.parse().unwrap_or(0), variant))
+            }
+            _ => "Parse".to_string(),
+        }
+    }
+
+    /// Do the entries measure the same step on the same input?
+    fn same_step(&self, other: &HistoryEntry) -> bool {
+        self.day == other.day
+            && self.step == other.step
+            && self.variant == other.variant
+            && self.input == other.input
+    }
+
+    /// The standard error of the median, approximated from the standard deviation.
+    fn median_error(&self) -> f64 {
+        // The median of normal samples has a variance pi/2 times the one of their mean
+        let samples = self.samples.max(1) as f64;
+        1.2533 * self.stddev_ns as f64 / samples.sqrt()
+    }
+}
+
+/// The benchmarks recorded over time, stored as JSON lines.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// Load the history from a file. A missing file is an empty history.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::from_json_lines(&content)
+                .with_context(|| format!("while parsing {}", path.display())),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error).with_context(|| format!("while reading {}", path.display())),
+        }
+    }
+
+    pub fn from_json_lines(content: &str) -> Result<Self> {
+        let entries = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                serde_json::from_str(line).with_context(|| format!("on line {}", idx + 1))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { entries })
+    }
+
+    /// Append entries to the history file, creating it if needed.
+    pub fn append(path: &Path, entries: &[HistoryEntry]) -> Result<()> {
+        let mut lines = String::new();
+        for entry in entries {
+            lines.push_str(&serde_json::to_string(entry)?);
+            lines.push('\n');
+        }
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(lines.as_bytes()))
+            .with_context(|| format!("while writing {}", path.display()))
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// The latest entry of the same step as `entry`, at `revision` if given.
+    pub fn baseline(&self, entry: &HistoryEntry, revision: Option<&str>) -> Option<&HistoryEntry> {
+        self.entries.iter().rev().find(|other| {
+            other.same_step(entry)
+                && revision.is_none_or(|revision| other.revision.as_deref() == Some(revision))
+        })
+    }
+
+    /// Compare new entries with their baselines.
+    pub fn compare(&self, entries: &[HistoryEntry], config: &CompareConfig) -> Vec<Comparison> {
+        entries
+            .iter()
+            .map(|current| {
+                let baseline = self.baseline(current, config.baseline.as_deref()).cloned();
+                let status = match &baseline {
+                    Some(baseline) => compare_medians(baseline, current, config.threshold),
+                    None => CompareStatus::New,
+                };
+
+                Comparison {
+                    baseline,
+                    current: current.clone(),
+                    status,
+                }
+            })
+            .collect()
+    }
+}
+
+/// How to compare benchmarks with the history.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompareConfig {
+    /// The change of the median, in percent, below which a step is unchanged.
+    pub threshold: f64,
+    /// The revision to compare with, rather than the latest benchmark.
+    pub baseline: Option<String>,
+}
+
+impl Default for CompareConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 5.0,
+            baseline: None,
+        }
+    }
+}
+
+/// A step is slower or faster only if its median changed by more than `threshold` percent
+/// and by more than the noise of both measures.
+fn compare_medians(
+    baseline: &HistoryEntry,
+    current: &HistoryEntry,
+    threshold: f64,
+) -> CompareStatus {
+    let delta = current.median_ns as f64 - baseline.median_ns as f64;
+    let noise = NOISE_SIGMAS * baseline.median_error().hypot(current.median_error());
+
+    if delta.abs() <= baseline.median_ns as f64 * threshold / 100.0 || delta.abs() <= noise {
+        CompareStatus::Unchanged
+    } else if delta > 0.0 {
+        CompareStatus::Slower
+    } else {
+        CompareStatus::Faster
+    }
+}
+
+/// The comparison of a benchmark with its baseline.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Comparison {
+    pub baseline: Option<HistoryEntry>,
+    pub current: HistoryEntry,
+    pub status: CompareStatus,
+}
+
+impl Comparison {
+    /// The change of the median, in percent.
+    ///
+    /// `None` without a baseline, or if its median is 0, below the resolution of the timer.
+    pub fn change(&self) -> Option<f64> {
+        let baseline = self.baseline.as_ref()?.median_ns;
+        if baseline == 0 {
+            return None;
+        }
+        Some((self.current.median_ns as f64 - baseline as f64) / baseline as f64 * 100.0)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CompareStatus {
+    /// There is no benchmark of this step in the history.
+    New,
+    Unchanged,
+    Faster,
+    Slower,
+}
+
+impl CompareStatus {
+    pub fn is_regression(self) -> bool {
+        self == CompareStatus::Slower
+    }
+}
+
+impl fmt::Display for CompareStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompareStatus::New => write!(f, "new"),
+            CompareStatus::Unchanged => write!(f, "ok"),
+            CompareStatus::Faster => write!(f, "faster"),
+            CompareStatus::Slower => write!(f, "SLOWER"),
+        }
+    }
+}
+
+/// A table of comparisons.
+pub struct ComparisonSummary<'a>(pub &'a [Comparison]);
+
+impl fmt::Display for ComparisonSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = [
+            "Day", "Step", "Baseline", "Revision", "Median", "Change", "Status",
+        ];
+        let median =
+            |entry: &HistoryEntry| format!("{:.2?}", Duration::from_nanos(entry.median_ns));
+        let rows: Vec<[String; 7]> = self
+            .0
+            .iter()
+            .map(|comparison| {
+                let baseline = comparison.baseline.as_ref();
+                [
+                    comparison.current.day.to_string(),
+                    comparison.current.name(),
+                    baseline.map_or("-".to_string(), median),
+                    baseline
+                        .and_then(|b| b.revision.clone())
+                        .unwrap_or_else(|| "-".to_string()),
+                    median(&comparison.current),
+                    comparison
+                        .change()
+                        .map_or("-".to_string(), |change| format!("{:+.1}%", change)),
+                    comparison.status.to_string(),
+                ]
+            })
+            .collect();
+
+        table::write_table(f, header, &rows)
+    }
+}
+
+/// The revision of the working directory, from `git describe --always --dirty`.
+pub fn current_revision() -> Option<String> {
+    let output = Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PartBench, Stats};
+
+    fn stats(median_ms: u64, stddev_us: u64) -> Stats {
+        let median = Duration::from_millis(median_ms);
+        Stats {
+            samples: 100,
+            min: median,
+            median,
+            mean: median,
+            p95: median,
+            stddev: Duration::from_micros(stddev_us),
+        }
+    }
+
+    fn entries(revision: &str, part1: Stats) -> Vec<HistoryEntry> {
+        let bench = DayBench {
+            day: 9,
+            parse: stats(1, 0),
+            parse_alloc: None,
+            parts: vec![
+                PartBench {
+                    part: 1,
+                    variant: crate::DEFAULT_VARIANT.to_string(),
+                    stats: Ok(part1),
+                    alloc: None,
+                },
+                PartBench {
+                    part: 2,
+                    variant: crate::DEFAULT_VARIANT.to_string(),
+                    stats: Err(crate::Error::InvalidPart(2)),
+                    alloc: None,
+                },
+            ],
+        };
+        let timestamp = DateTime::parse_from_rfc3339("2020-12-09T06:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        HistoryEntry::from_bench("inputs/09.txt", &bench, Some(revision), timestamp)
+    }
+
+    #[test]
+    fn test_from_bench() {
+        let entries = entries("abc1234", stats(10, 0));
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name(), "Parse");
+        assert_eq!(entries[1].name(), "Part 1");
+        assert_eq!(entries[1].median_ns, 10_000_000);
+        assert_eq!(entries[1].timestamp, "2020-12-09T06:00:00Z");
+    }
+
+    #[test]
+    fn test_json_lines_round_trip() {
+        let path = std::env::temp_dir().join(format!("advent2020-history-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let first = entries("abc1234", stats(10, 0));
+        History::append(&path, &first).unwrap();
+        History::append(&path, &first).unwrap();
+        let history = History::load(&path).unwrap();
+        assert_eq!(history.entries().len(), 4);
+        assert_eq!(history.entries()[..2], first[..]);
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(History::load(&path).unwrap(), History::default());
+        assert!(History::from_json_lines("{}\n").is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut history = History::default();
+        history.entries.extend(entries("old", stats(10, 100)));
+        history.entries.extend(entries("new", stats(20, 100)));
+        let config = CompareConfig::default();
+
+        let status = |part1: Stats, config: &CompareConfig| {
+            history.compare(&entries("head", part1), config)[1].status
+        };
+
+        // The latest entry is the baseline by default
+        assert_eq!(status(stats(25, 100), &config), CompareStatus::Slower);
+        assert_eq!(status(stats(15, 100), &config), CompareStatus::Faster);
+        // Within the threshold
+        assert_eq!(status(stats(20, 100), &config), CompareStatus::Unchanged);
+        // Within the noise
+        assert_eq!(
+            status(stats(25, 100_000), &config),
+            CompareStatus::Unchanged
+        );
+
+        let old = CompareConfig {
+            baseline: Some("old".to_string()),
+            ..CompareConfig::default()
+        };
+        assert_eq!(status(stats(20, 100), &old), CompareStatus::Slower);
+        assert_eq!(
+            history.compare(
+                &entries("head", stats(1, 0)),
+                &CompareConfig {
+                    baseline: Some("unknown".to_string()),
+                    ..CompareConfig::default()
+                }
+            )[1]
+            .status,
+            CompareStatus::New
+        );
+    }
+
+    #[test]
+    fn test_change() {
+        let comparison = |baseline: Option<Stats>| {
+            let mut history = History::default();
+            if let Some(baseline) = baseline {
+                history.entries.extend(entries("old", baseline));
+            }
+            history
+                .compare(&entries("head", stats(15, 0)), &CompareConfig::default())
+                .remove(1)
+        };
+
+        assert_eq!(comparison(Some(stats(10, 0))).change(), Some(50.0));
+        assert_eq!(comparison(Some(stats(0, 0))).change(), None);
+        assert_eq!(comparison(None).change(), None);
+    }
+}
@@ -0,0 +1,419 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read as _};
+use std::path::{Path, PathBuf};
+
+use crate::registry::inputs_dir;
+use crate::Error;
+
+/// The environment variable overriding the root of the inputs, see [`EnvDirectory`].
+pub const INPUTS_ENV: &str = "AOC_INPUTS";
+
+/// Where the puzzle inputs come from, so that the solvers never touch the filesystem.
+pub trait InputProvider: fmt::Debug + Send + Sync {
+    /// Read the whole input of a day of a year.
+    fn read(&self, year: u32, day: u32) -> Result<String, Error>;
+
+    /// Describe where the input of a day is read from, e.g. in the diagnostics.
+    fn describe(&self, year: u32, day: u32) -> String;
+
+    /// The file the input of a day is read from, if it is read from a file.
+    fn path(&self, _year: u32, _day: u32) -> Option<PathBuf> {
+        None
+    }
+
+    /// Is there an input for the day? Always true for the inputs that are not files.
+    fn exists(&self, year: u32, day: u32) -> bool {
+        self.path(year, day).is_none_or(|path| path.exists())
+    }
+}
+
+/// The `NN.txt` inputs in a directory laid out like `inputs/`: the inputs of 2020 at its root,
+/// and the ones of the other years in a directory per year.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Directory {
+    pub root: PathBuf,
+}
+
+impl Directory {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// The directory of the inputs of a year.
+    pub fn year_dir(&self, year: u32) -> PathBuf {
+        let dir = inputs_dir(year);
+        if dir.as_os_str().is_empty() {
+            self.root.clone()
+        } else {
+            self.root.join(dir)
+        }
+    }
+
+    fn file(&self, year: u32, day: u32) -> PathBuf {
+        self.year_dir(year).join(format!("{:02}.txt", day))
+    }
+}
+
+/// The `inputs/` directory of the working directory.
+impl Default for Directory {
+    fn default() -> Self {
+        Self::new("inputs")
+    }
+}
+
+impl InputProvider for Directory {
+    fn read(&self, year: u32, day: u32) -> Result<String, Error> {
+        read_file(&self.file(year, day))
+    }
+
+    fn describe(&self, year: u32, day: u32) -> String {
+        self.file(year, day).display().to_string()
+    }
+
+    fn path(&self, year: u32, day: u32) -> Option<PathBuf> {
+        Some(self.file(year, day))
+    }
+}
+
+/// A [`Directory`] whose root is given by an environment variable, or the default one if the
+/// variable is not set.
+///
+/// The variable is read on each access.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EnvDirectory {
+    pub var: String,
+}
+
+impl EnvDirectory {
+    pub fn new(var: impl Into<String>) -> Self {
+        Self { var: var.into() }
+    }
+
+    /// The directory the variable currently points to.
+    pub fn directory(&self) -> Directory {
+        Self::resolve(env::var_os(&self.var))
+    }
+
+    /// The directory for a value of the variable, the default one if unset or empty.
+    fn resolve(value: Option<OsString>) -> Directory {
+        match value.filter(|root| !root.is_empty()) {
+            Some(root) => Directory::new(root),
+            None => Directory::default(),
+        }
+    }
+}
+
+/// The directory given by [`INPUTS_ENV`].
+impl Default for EnvDirectory {
+    fn default() -> Self {
+        Self::new(INPUTS_ENV)
+    }
+}
+
+impl InputProvider for EnvDirectory {
+    fn read(&self, year: u32, day: u32) -> Result<String, Error> {
+        self.directory().read(year, day)
+    }
+
+    fn describe(&self, year: u32, day: u32) -> String {
+        self.directory().describe(year, day)
+    }
+
+    fn path(&self, year: u32, day: u32) -> Option<PathBuf> {
+        self.directory().path(year, day)
+    }
+}
+
+/// Inputs held in memory, such as examples or generated data.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InMemory {
+    inputs: BTreeMap<(u32, u32), String>,
+}
+
+impl InMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace the input of a day.
+    pub fn with(mut self, year: u32, day: u32, content: impl Into<String>) -> Self {
+        self.insert(year, day, content);
+        self
+    }
+
+    pub fn insert(&mut self, year: u32, day: u32, content: impl Into<String>) {
+        self.inputs.insert((year, day), content.into());
+    }
+}
+
+impl InputProvider for InMemory {
+    fn read(&self, year: u32, day: u32) -> Result<String, Error> {
+        self.inputs
+            .get(&(year, day))
+            .cloned()
+            .ok_or_else(|| not_found(self.describe(year, day)))
+    }
+
+    fn describe(&self, year: u32, day: u32) -> String {
+        format!("<memory {}/{:02}>", year, day)
+    }
+
+    fn exists(&self, year: u32, day: u32) -> bool {
+        self.inputs.contains_key(&(year, day))
+    }
+}
+
+/// Inputs compiled into the binary, as `(year, day, content)` entries:
+///
+/// ```
+/// use advent2020::{Embedded, InputProvider};
+///
+/// static INPUTS: Embedded = Embedded(&[(2020, 1, "1721\n979\n366\n")]);
+///
+/// assert_eq!(INPUTS.read(2020, 1).unwrap(), "1721\n979\n366\n");
+/// assert!(!INPUTS.exists(2020, 2));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Embedded(pub &'static [(u32, u32, &'static str)]);
+
+impl Embedded {
+    fn get(&self, year: u32, day: u32) -> Option<&'static str> {
+        self.0
+            .iter()
+            .find(|&&(y, d, _)| (y, d) == (year, day))
+            .map(|&(_, _, content)| content)
+    }
+}
+
+impl InputProvider for Embedded {
+    fn read(&self, year: u32, day: u32) -> Result<String, Error> {
+        self.get(year, day)
+            .map(str::to_string)
+            .ok_or_else(|| not_found(self.describe(year, day)))
+    }
+
+    fn describe(&self, year: u32, day: u32) -> String {
+        format!("<embedded {}/{:02}>", year, day)
+    }
+
+    fn exists(&self, year: u32, day: u32) -> bool {
+        self.get(year, day).is_some()
+    }
+}
+
+/// The inputs present in `inputs/` when the crate was built, with the `embedded-inputs`
+/// feature. Empty without it.
+#[cfg(feature = "embedded-inputs")]
+pub static EMBEDDED_INPUTS: Embedded =
+    Embedded(include!(concat!(env!("OUT_DIR"), "/embedded_inputs.rs")));
+#[cfg(not(feature = "embedded-inputs"))]
+pub static EMBEDDED_INPUTS: Embedded = Embedded(&[]);
+
+/// Where the puzzle input of a day comes from, as selected on the command line.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum Input {
+    /// The default location: the [`EMBEDDED_INPUTS`] if they have the day and [`INPUTS_ENV`]
+    /// is not set, the [`EnvDirectory`] of [`INPUTS_ENV`] otherwise.
+    #[default]
+    Default,
+    /// The [`EnvDirectory`] of [`INPUTS_ENV`], even if the day has an embedded input.
+    Disk,
+    /// A file anywhere on the disk.
+    Path(PathBuf),
+    /// The standard input of the process.
+    ///
+    /// Note that stdin can only be read once: read it a single time and reuse the content.
+    Stdin,
+    /// An input already in memory.
+    Content(String),
+}
+
+impl Input {
+    /// Parse a command-line argument: `-` means stdin, anything else is a path.
+    pub fn from_arg(arg: &str) -> Self {
+        if arg == "-" {
+            Input::Stdin
+        } else {
+            Input::Path(arg.into())
+        }
+    }
+}
+
+/// Does the default input of a day come from the [`EMBEDDED_INPUTS`]?
+fn use_embedded(year: u32, day: u32) -> bool {
+    let overridden = env::var_os(INPUTS_ENV).is_some_and(|root| !root.is_empty());
+    !overridden && EMBEDDED_INPUTS.exists(year, day)
+}
+
+impl InputProvider for Input {
+    fn read(&self, year: u32, day: u32) -> Result<String, Error> {
+        match self {
+            Input::Default if use_embedded(year, day) => EMBEDDED_INPUTS.read(year, day),
+            Input::Default | Input::Disk => EnvDirectory::default().read(year, day),
+            Input::Path(path) => read_file(path),
+            Input::Stdin => {
+                let mut content = String::new();
+                io::stdin()
+                    .read_to_string(&mut content)
+                    .map_err(|e| Error::input_unavailable(self.describe(year, day), e))?;
+                Ok(content)
+            }
+            Input::Content(content) => Ok(content.clone()),
+        }
+    }
+
+    fn describe(&self, year: u32, day: u32) -> String {
+        match self {
+            Input::Default if use_embedded(year, day) => EMBEDDED_INPUTS.describe(year, day),
+            Input::Default | Input::Disk => EnvDirectory::default().describe(year, day),
+            _ => self.to_string(),
+        }
+    }
+
+    fn path(&self, year: u32, day: u32) -> Option<PathBuf> {
+        match self {
+            Input::Default if use_embedded(year, day) => None,
+            Input::Default | Input::Disk => EnvDirectory::default().path(year, day),
+            Input::Path(path) => Some(path.clone()),
+            Input::Stdin | Input::Content(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Input::Default | Input::Disk => write!(f, "inputs/XX.txt"),
+            Input::Path(path) => write!(f, "{}", path.display()),
+            Input::Stdin => write!(f, "<stdin>"),
+            Input::Content(_) => write!(f, "<memory>"),
+        }
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, Error> {
+    let unavailable = |e| Error::input_unavailable(path.display().to_string(), e);
+    let mut file = File::open(path).map_err(unavailable)?;
+
+    let mut content = String::new();
+    file.read_to_string(&mut content).map_err(unavailable)?;
+
+    Ok(content)
+}
+
+fn not_found(origin: String) -> Error {
+    Error::input_unavailable(origin, io::ErrorKind::NotFound.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_from_arg() {
+        assert_eq!(Input::from_arg("-"), Input::Stdin);
+        assert_eq!(
+            Input::from_arg("some/file.txt"),
+            Input::Path("some/file.txt".into())
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        // No input of day 25 is ever embedded
+        assert_eq!(Input::Default.describe(2020, 25), "inputs/25.txt");
+        assert_eq!(Input::Default.describe(2021, 25), "inputs/2021/25.txt");
+        assert_eq!(Input::Disk.describe(2020, 3), "inputs/03.txt");
+        assert_eq!(Input::Stdin.describe(2020, 3), "<stdin>");
+    }
+
+    #[test]
+    fn test_path() {
+        assert_eq!(Input::Default.path(2020, 25), Some("inputs/25.txt".into()));
+        assert_eq!(Input::Disk.path(2020, 3), Some("inputs/03.txt".into()));
+        assert_eq!(Input::Stdin.path(2020, 3), None);
+    }
+
+    #[test]
+    fn test_read_content() {
+        let input = Input::Content("1\n2\n".to_string());
+        assert_eq!(input.read(2020, 1).unwrap(), "1\n2\n");
+    }
+
+    #[test]
+    fn test_read_missing_file_names_path() {
+        let input = Input::Path("does/not/exist.txt".into());
+        match input.read(2020, 1).unwrap_err() {
+            Error::InputUnavailable { path, .. } => assert_eq!(path, "does/not/exist.txt"),
+            error => panic!("Unexpected error {:?}", error),
+        }
+    }
+
+    #[test]
+    fn test_directory() {
+        let root = env::temp_dir().join(format!("advent2020-inputs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("2021")).unwrap();
+        fs::write(root.join("01.txt"), "2020\n").unwrap();
+        fs::write(root.join("2021").join("01.txt"), "2021\n").unwrap();
+
+        let directory = Directory::new(&root);
+        assert_eq!(directory.read(2020, 1).unwrap(), "2020\n");
+        assert_eq!(directory.read(2021, 1).unwrap(), "2021\n");
+        assert!(!directory.exists(2020, 2));
+        assert!(directory.read(2020, 2).is_err());
+
+        // The environment is not modified, other tests read it concurrently
+        assert_eq!(EnvDirectory::resolve(None), Directory::default());
+        assert_eq!(
+            EnvDirectory::resolve(Some(OsString::new())),
+            Directory::default()
+        );
+        assert_eq!(
+            EnvDirectory::resolve(Some(root.clone().into_os_string())),
+            directory
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_in_memory() {
+        let inputs = InMemory::new().with(2020, 1, "1\n").with(2021, 1, "2\n");
+
+        assert_eq!(inputs.read(2021, 1).unwrap(), "2\n");
+        assert!(inputs.exists(2020, 1));
+        match inputs.read(2020, 2).unwrap_err() {
+            Error::InputUnavailable { path, .. } => assert_eq!(path, "<memory 2020/02>"),
+            error => panic!("Unexpected error {:?}", error),
+        }
+    }
+
+    #[test]
+    fn test_embedded() {
+        let inputs = Embedded(&[(2020, 1, "1\n"), (2021, 1, "2\n")]);
+
+        assert_eq!(inputs.read(2021, 1).unwrap(), "2\n");
+        assert_eq!(inputs.describe(2020, 1), "<embedded 2020/01>");
+        assert!(!inputs.exists(2020, 2));
+        assert!(inputs.read(2020, 2).is_err());
+    }
+
+    #[cfg(feature = "embedded-inputs")]
+    #[test]
+    fn test_embedded_inputs() {
+        assert!(EMBEDDED_INPUTS.exists(2020, 1));
+        assert_eq!(Input::Default.describe(2020, 1), "<embedded 2020/01>");
+        assert_eq!(Input::Default.path(2020, 1), None);
+        assert_eq!(
+            Input::Default.read(2020, 1).unwrap(),
+            Input::Disk.read(2020, 1).unwrap()
+        );
+    }
+}
@@ -0,0 +1,524 @@
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use crate::registry::Registry;
+use crate::runner::{map_parallel, Runner, Timed};
+use crate::utils::collecting_all_errors;
+use crate::{AllocStats, Answer, Error, Location, PartReport, DEFAULT_VARIANT};
+
+/// The hidden subcommand running a single part in a child process, see [`run_child`].
+///
+/// The child is run as `<exe> child YEAR DAY PART VARIANT`, with the input on its standard
+/// input.
+pub const CHILD_SUBCOMMAND: &str = "child";
+
+/// Precedes the report at the end of the standard output of a child, so that anything the
+/// solver prints does not corrupt it.
+const REPORT_MARKER: &str = "@advent2020-report ";
+
+/// How to run each part in a child process.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Isolation {
+    /// The executable handling [`CHILD_SUBCOMMAND`], usually the current one.
+    pub exe: PathBuf,
+    /// The wall-clock time after which the child is killed.
+    pub timeout: Duration,
+    /// The maximum address space of the child, in bytes. Only enforced on Unix.
+    pub memory_limit: Option<u64>,
+}
+
+impl Isolation {
+    pub fn new(exe: PathBuf) -> Self {
+        Self {
+            exe,
+            timeout: Duration::from_secs(60),
+            memory_limit: Some(4 << 30),
+        }
+    }
+}
+
+/// Run the selected parts of every day of the runner, each in a child process.
+///
+/// A part that times out, exceeds the memory limit, panics or is killed fails with a distinct
+/// [`Error`], without stopping the other parts.
+pub(crate) fn run_days_isolated(
+    runner: &Runner,
+    isolation: &Isolation,
+    notify: &(dyn Fn(&PartReport) + Sync),
+) -> Vec<PartReport> {
+    let Runner {
+        year,
+        ref input,
+        variants,
+        jobs,
+        repetitions,
+        ..
+    } = *runner;
+
+    let known = runner.variant_known();
+    // The input of each day is read once here, but parsed by each child
+    let tasks: Vec<_> = runner
+        .days
+        .iter()
+        .flat_map(|&day| {
+            let prepared = Registry::global()
+                .get(year, day)
+                .and_then(|solver| Ok((solver, input.read(year, day)?)));
+            let origin = input.describe(year, day);
+
+            runner.parts.iter().flat_map(move |&part| {
+                let selected = match &prepared {
+                    Ok((solver, _)) => variants.select(*solver, part, known),
+                    Err(_) => vec![DEFAULT_VARIANT],
+                };
+                let content = match &prepared {
+                    Ok((_, content)) => Ok(content.clone()),
+                    Err(error) => Err(error.clone()),
+                };
+                let origin = origin.clone();
+                selected
+                    .into_iter()
+                    .map(move |variant| (day, part, variant, origin.clone(), content.clone()))
+            })
+        })
+        .collect();
+
+    let jobs = jobs.clamp(1, tasks.len().max(1));
+
+    map_parallel(&tasks, jobs, |(day, part, variant, origin, content)| {
+        let (day, part) = (*day, *part);
+        let report = content.clone().and_then(|content| {
+            run_isolated(isolation, repetitions, year, day, part, variant, &content)
+        });
+        let (answer, report) = match report {
+            Ok(report) => (report.answer(day, part), Some(report)),
+            Err(error) => (Err(error), None),
+        };
+        let report = report.as_ref();
+
+        let report = PartReport {
+            day,
+            part,
+            variant: variant.to_string(),
+            input: origin.clone(),
+            answer,
+            parse_time: report
+                .and_then(|r| r.parse_time_ns)
+                .map(Duration::from_nanos),
+            solve_time: report
+                .and_then(|r| r.solve_time_ns)
+                .map(Duration::from_nanos),
+            parse_alloc: report.and_then(|r| r.parse_alloc),
+            solve_alloc: report.and_then(|r| r.solve_alloc),
+            // Each child parses the input again
+            shared_parse: false,
+            jobs,
+        };
+        notify(&report);
+        report
+    })
+}
+
+/// Run a part in the current process, and return the report the child writes at the end of
+/// its standard output: a JSON object after a marker.
+///
+/// The parsing and the part run `repetitions` times, and their fastest run is reported.
+pub fn run_child(
+    year: u32,
+    day: u32,
+    part: u8,
+    variant: &str,
+    content: &str,
+    repetitions: usize,
+) -> String {
+    let report = match Registry::global().get(year, day) {
+        Ok(solver) => {
+            let parse = Timed::fastest(repetitions, || solver.parse(content));
+            let mut report = ChildReport {
+                parse_time_ns: parse.time.map(|t| t.as_nanos() as u64),
+                parse_alloc: parse.alloc,
+                ..ChildReport::default()
+            };
+            match parse.result {
+                Ok(input) => {
+                    let solve = Timed::fastest(repetitions, || input.solve(part, variant));
+                    report.solve_time_ns = solve.time.map(|t| t.as_nanos() as u64);
+                    report.solve_alloc = solve.alloc;
+                    match solve.result {
+                        Ok(answer) => report.answer = Some(answer),
+                        Err(error) => report.error = Some(ChildError::from(&error)),
+                    }
+                }
+                Err(error) => report.error = Some(ChildError::from(&error)),
+            }
+            report
+        }
+        Err(error) => ChildReport {
+            error: Some(ChildError::from(&error)),
+            ..ChildReport::default()
+        },
+    };
+
+    let json = serde_json::to_string(&report).expect("Cannot serialize the report");
+    format!("{}{}", REPORT_MARKER, json)
+}
+
+/// The report at the end of the standard output of a child, after anything the solver printed.
+fn parse_report(stdout: &str) -> Result<ChildReport, String> {
+    let start = stdout.rfind(REPORT_MARKER).ok_or("no report")? + REPORT_MARKER.len();
+    serde_json::from_str(stdout[start..].trim()).map_err(|e| e.to_string())
+}
+
+/// What a child reports about its part.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+struct ChildReport {
+    answer: Option<Answer>,
+    error: Option<ChildError>,
+    parse_time_ns: Option<u64>,
+    solve_time_ns: Option<u64>,
+    parse_alloc: Option<AllocStats>,
+    solve_alloc: Option<AllocStats>,
+}
+
+impl ChildReport {
+    fn answer(&self, day: u32, part: u8) -> Result<Answer, Error> {
+        match (&self.answer, &self.error) {
+            (_, Some(error)) => Err(error.to_error(day, part)),
+            (Some(answer), None) => Ok(answer.clone()),
+            (None, None) => Err(Error::Child {
+                day,
+                part,
+                message: "no answer".to_string(),
+            }),
+        }
+    }
+}
+
+/// An [`Error`] sent by a child, as its sources cannot be sent.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+enum ChildError {
+    Parse {
+        message: String,
+        location: Option<Location>,
+    },
+    Solve {
+        message: String,
+    },
+    Other {
+        message: String,
+    },
+}
+
+impl From<&Error> for ChildError {
+    fn from(error: &Error) -> Self {
+        match error {
+            Error::Parse {
+                location, source, ..
+            } => ChildError::Parse {
+                message: format!("{:#}", source),
+                location: *location,
+            },
+            Error::Solve { source, .. } => ChildError::Solve {
+                message: format!("{:#}", source),
+            },
+            _ => ChildError::Other {
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+impl ChildError {
+    fn to_error(&self, day: u32, part: u8) -> Error {
+        match self {
+            ChildError::Parse { message, location } => Error::Parse {
+                day,
+                location: *location,
+                source: Arc::new(anyhow!("{}", message)),
+            },
+            ChildError::Solve { message } => Error::solve(day, part, anyhow!("{}", message)),
+            ChildError::Other { message } => Error::Child {
+                day,
+                part,
+                message: message.clone(),
+            },
+        }
+    }
+}
+
+fn run_isolated(
+    isolation: &Isolation,
+    repetitions: usize,
+    year: u32,
+    day: u32,
+    part: u8,
+    variant: &str,
+    content: &str,
+) -> Result<ChildReport, Error> {
+    let mut command = Command::new(&isolation.exe);
+    command.args([
+        CHILD_SUBCOMMAND,
+        &year.to_string(),
+        &day.to_string(),
+        &part.to_string(),
+        variant,
+    ]);
+    if collecting_all_errors() {
+        command.arg("--all-errors");
+    }
+    if repetitions > 1 {
+        command.args(["--repetitions", &repetitions.to_string()]);
+    }
+    #[cfg(unix)]
+    {
+        if let Some(limit) = isolation.memory_limit {
+            limit_memory(&mut command, limit);
+        }
+    }
+
+    let outcome =
+        run_with_timeout(command, content, isolation.timeout).map_err(|e| Error::Child {
+            day,
+            part,
+            message: format!("cannot run {}: {}", isolation.exe.display(), e),
+        })?;
+
+    outcome.into_report(day, part, isolation.timeout)
+}
+
+/// How a child process ended.
+struct Outcome {
+    /// `None` if the child was killed after the timeout.
+    status: Option<ExitStatus>,
+    stdout: String,
+    stderr: String,
+}
+
+impl Outcome {
+    fn into_report(self, day: u32, part: u8, timeout: Duration) -> Result<ChildReport, Error> {
+        let status = match self.status {
+            Some(status) => status,
+            None => {
+                return Err(Error::Timeout {
+                    day,
+                    part,
+                    limit: timeout,
+                })
+            }
+        };
+
+        if status.success() {
+            return parse_report(&self.stdout).map_err(|e| Error::Child {
+                day,
+                part,
+                message: format!("invalid report: {}", e),
+            });
+        }
+
+        // The exit code of a panicking Rust program
+        if status.code() == Some(101) {
+            return Err(Error::Panic {
+                day,
+                part,
+                message: panic_message(&self.stderr),
+            });
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+
+            if let Some(signal) = status.signal() {
+                // The standard library aborts when an allocation fails
+                if self.stderr.contains("memory allocation of") {
+                    return Err(Error::OutOfMemory { day, part });
+                }
+                return Err(Error::Signal { day, part, signal });
+            }
+        }
+
+        Err(Error::Child {
+            day,
+            part,
+            message: format!("{}: {}", status, self.stderr.trim()),
+        })
+    }
+}
+
+/// Run a command with `content` on its standard input, and kill it after `timeout`.
+fn run_with_timeout(mut command: Command, content: &str, timeout: Duration) -> io::Result<Outcome> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // The pipes are handled on other threads, so that a full pipe cannot block the child
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let content = content.to_string();
+    let writer = thread::spawn(move || {
+        // The child may exit without reading its input
+        let _ = stdin.write_all(content.as_bytes());
+    });
+    let stdout = read_in_background(child.stdout.take().expect("stdout is piped"));
+    let stderr = read_in_background(child.stderr.take().expect("stderr is piped"));
+
+    let status = wait_with_timeout(&mut child, timeout)?;
+
+    let _ = writer.join();
+    Ok(Outcome {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+fn read_in_background(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = pipe.read_to_end(&mut buffer);
+        String::from_utf8_lossy(&buffer).into_owned()
+    })
+}
+
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+    let start = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if start.elapsed() >= timeout {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[cfg(unix)]
+fn limit_memory(command: &mut Command, bytes: u64) {
+    use std::os::unix::process::CommandExt;
+
+    let limit = libc::rlimit {
+        rlim_cur: bytes as libc::rlim_t,
+        rlim_max: bytes as libc::rlim_t,
+    };
+    // Safety: setrlimit is async-signal-safe, and the closure does not allocate
+    unsafe {
+        command.pre_exec(move || {
+            if libc::setrlimit(libc::RLIMIT_AS, &limit) == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::last_os_error())
+            }
+        });
+    }
+}
+
+/// The message of a panic, from the standard error of the process.
+fn panic_message(stderr: &str) -> String {
+    let mut lines = stderr
+        .lines()
+        .skip_while(|line| !line.contains("panicked at"));
+    let location = lines.next();
+    let message: Vec<_> = lines
+        .take_while(|line| !line.starts_with("note:") && !line.starts_with("stack backtrace:"))
+        .collect();
+
+    match location {
+        Some(_) if !message.is_empty() => message.join(" "),
+        _ => stderr.trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(script: &str, timeout: Duration) -> Result<ChildReport, Error> {
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        run_with_timeout(command, "1\n2\n", timeout)
+            .unwrap()
+            .into_report(1, 2, timeout)
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let stderr = "\
+thread 'main' panicked at src/day08.rs:60:9:
+index out of bounds: the len is 3 but the index is 7
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+";
+        assert_eq!(
+            panic_message(stderr),
+            "index out of bounds: the len is 3 but the index is 7"
+        );
+        assert_eq!(panic_message("oops\n"), "oops");
+    }
+
+    #[test]
+    fn test_child_report_round_trip() {
+        let report = run_child(2020, 1, 1, DEFAULT_VARIANT, "1010\n1010\n", 1);
+        let report = parse_report(&format!("printed by the solver{}", report)).unwrap();
+        assert_eq!(report.answer, Some(Answer::Integer(1020100)));
+        assert!(report.parse_time_ns.is_some());
+
+        let report = run_child(2020, 1, 1, DEFAULT_VARIANT, "1\nx\n", 1);
+        let report = parse_report(&report).unwrap();
+        assert!(matches!(
+            report.answer(1, 1),
+            Err(Error::Parse {
+                location: Some(Location { line: 2, .. }),
+                ..
+            })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_outcomes() {
+        let second = Duration::from_secs(1);
+
+        let script =
+            r#"cat >/dev/null; echo '{"answer": 1}'; echo '@advent2020-report {"answer": 42}'"#;
+        let report = outcome(script, second).unwrap();
+        assert_eq!(report.answer(1, 2).unwrap(), Answer::Integer(42));
+        assert!(matches!(
+            outcome(r#"echo '{"answer": 42}'"#, second),
+            Err(Error::Child { .. })
+        ));
+
+        assert!(matches!(
+            outcome("sleep 5", Duration::from_millis(50)),
+            Err(Error::Timeout { .. })
+        ));
+        assert!(matches!(
+            outcome("echo \"thread 'main' panicked at x:\nboom\" >&2; exit 101", second),
+            Err(Error::Panic { message, .. }) if message == "boom"
+        ));
+        assert!(matches!(
+            outcome(
+                "echo 'memory allocation of 8 bytes failed' >&2; kill -ABRT $$",
+                second
+            ),
+            Err(Error::OutOfMemory { .. })
+        ));
+        assert!(matches!(
+            outcome("kill -9 $$", second),
+            Err(Error::Signal { signal: 9, .. })
+        ));
+        assert!(matches!(
+            outcome("exit 3", second),
+            Err(Error::Child { .. })
+        ));
+    }
+}
@@ -3,6 +3,10 @@
 #[cfg(all(test, feature = "nightly"))]
 extern crate test;
 
+mod alloc;
+mod answer;
+mod answers;
+mod bench;
 mod day01;
 mod day02;
 mod day03;
@@ -15,19 +19,67 @@ mod day09;
 mod day10;
 mod day11;
 mod day12;
+mod error;
+mod fetch;
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing;
+mod history;
+mod input;
+mod isolation;
+mod output;
+mod registry;
+mod runner;
+mod scaffold;
+mod selection;
+mod submit;
+mod table;
 mod utils;
-
-use std::fmt::Display;
-use std::fs::File;
-use std::io::Read as _;
-
-use anyhow::{Context as _, Result};
+mod watch;
+
+use std::hint::black_box;
+
+use anyhow::Result;
+
+pub use alloc::AllocStats;
+#[cfg(feature = "count-allocs")]
+pub use alloc::CountingAllocator;
+pub use answer::Answer;
+pub use answers::{AnswerStore, Check, CheckStatus, CheckSummary, DEFAULT_ANSWERS_PATH};
+pub use bench::{BenchConfig, DayBench, PartBench, Stats};
+pub use error::{Error, Location};
+pub use fetch::{
+    is_cached, session_token, Fetched, Fetcher, BASE_URL_ENV, DEFAULT_BASE_URL,
+    DEFAULT_SESSION_PATH, SESSION_ENV,
+};
+pub use history::{
+    current_revision, CompareConfig, CompareStatus, Comparison, ComparisonSummary, History,
+    HistoryEntry, DEFAULT_HISTORY_PATH,
+};
+pub use input::{
+    Directory, Embedded, EnvDirectory, InMemory, Input, InputProvider, EMBEDDED_INPUTS, INPUTS_ENV,
+};
+pub use isolation::{run_child, Isolation, CHILD_SUBCOMMAND};
+pub use output::{write_benches, write_reports, BenchRecord, Format, Record};
+pub use registry::{answers_path, default_year, DAYS};
+pub use runner::{disagreements, PartReport, Runner, Summary, Variants};
+pub use scaffold::new_day;
+pub use selection::parse_days;
+pub use submit::{
+    Refusal, Response, Submission, SubmissionLog, Submitter, Verdict, DEFAULT_SUBMISSIONS_PATH,
+};
+pub use utils::with_all_parse_errors;
+pub use watch::{AnswerChange, PreviousAnswers, WatchSummary, Watcher};
+
+use registry::{Event, Registry};
+use runner::Timed;
 
 pub trait Challenge {
     const DAY_NUMBER: u32;
+    /// The year of the event of the challenge.
+    const YEAR: u32 = 2020;
 
-    type InputType;
-    type OutputType: Display;
+    type InputType: Send + Sync;
+    type OutputType: Into<Answer>;
 
     fn part1(input: &Self::InputType) -> Result<Self::OutputType>;
     fn part2(input: &Self::InputType) -> Result<Self::OutputType>;
@@ -40,88 +92,216 @@ pub trait Challenge {
     fn solve2(content: &str) -> Result<Self::OutputType> {
         Self::part2(&Self::parse(content)?)
     }
+
+    /// Alternative implementations of the parts, selectable by name.
+    ///
+    /// `part1` and `part2` are always available as the [`DEFAULT_VARIANT`].
+    fn variants() -> Vec<Variant<Self>>
+    where
+        Self: Sized,
+    {
+        Vec::new()
+    }
+}
+
+/// The name of the `part1` and `part2` implementations of a challenge.
+pub const DEFAULT_VARIANT: &str = "default";
+
+/// The signature of `Challenge::part1` and `Challenge::part2`.
+pub type PartFn<C> = fn(&<C as Challenge>::InputType) -> Result<<C as Challenge>::OutputType>;
+
+/// A named alternative implementation of a part.
+pub struct Variant<C: Challenge> {
+    pub part: u8,
+    pub name: &'static str,
+    pub solve: PartFn<C>,
 }
 
 trait ChallengeSolver {
-    fn solve1(&self, content: &str) -> Result<String>;
-    fn solve2(&self, content: &str) -> Result<String>;
+    /// The `DAY_NUMBER` of the challenge.
+    fn day(&self) -> u32;
+
+    /// The `YEAR` of the challenge.
+    fn year(&self) -> u32;
+
+    /// The names of the implementations of a part, the default one first.
+    fn variants(&self, part: u8) -> Vec<&'static str>;
+
+    /// Parse the content once, to solve any part on it.
+    fn parse(&self, content: &str) -> Timed<Box<dyn ParsedInput>>;
+
+    /// Benchmark the parsing and every variant of both parts separately.
+    fn bench(&self, content: &str, config: &BenchConfig) -> Result<DayBench, Error>;
+}
+
+/// The input of a day, parsed and shared by its parts.
+trait ParsedInput: Send + Sync {
+    /// Solve a part with the named variant.
+    fn solve(&self, part: u8, variant: &str) -> Timed<Answer>;
 }
 
 struct ChallengeImpl<C: Challenge>(C);
 
-impl<C: Challenge> ChallengeSolver for ChallengeImpl<C> {
-    fn solve1(&self, content: &str) -> Result<String> {
-        Ok(format!("{}", C::solve1(content)?))
+impl<C: Challenge> ChallengeImpl<C> {
+    /// The implementations of a part, the default one first.
+    fn implementations(part: u8) -> Vec<(&'static str, PartFn<C>)> {
+        let default: PartFn<C> = match part {
+            1 => C::part1,
+            2 => C::part2,
+            _ => return Vec::new(),
+        };
+        let variants = C::variants()
+            .into_iter()
+            .filter(|variant| variant.part == part)
+            .map(|variant| (variant.name, variant.solve));
+
+        std::iter::once((DEFAULT_VARIANT, default))
+            .chain(variants)
+            .collect()
     }
+}
 
-    fn solve2(&self, content: &str) -> Result<String> {
-        Ok(format!("{}", C::solve2(content)?))
+struct Parsed<C: Challenge>(C::InputType);
+
+impl<C: Challenge> ParsedInput for Parsed<C> {
+    fn solve(&self, part: u8, variant: &str) -> Timed<Answer> {
+        if !(1..=2).contains(&part) {
+            return Timed::skipped(Error::InvalidPart(part));
+        }
+
+        let implementations = ChallengeImpl::<C>::implementations(part);
+        match implementations.iter().find(|(name, _)| *name == variant) {
+            Some(&(_, part_fn)) => Timed::measure(|| {
+                part_fn(&self.0)
+                    .map(Into::into)
+                    .map_err(|e| Error::solve(C::DAY_NUMBER, part, e))
+            }),
+            None => Timed::skipped(Error::UnknownVariant {
+                day: C::DAY_NUMBER,
+                part,
+                name: variant.to_string(),
+                available: implementations.iter().map(|(name, _)| *name).collect(),
+            }),
+        }
     }
 }
 
-static CHALLENGES: &[&(dyn ChallengeSolver + Sync + Send)] = &[
-    &ChallengeImpl(day01::Day01),
-    &ChallengeImpl(day02::Day02),
-    &ChallengeImpl(day03::Day03),
-    &ChallengeImpl(day04::Day04),
-    &ChallengeImpl(day05::Day05),
-    &ChallengeImpl(day06::Day06),
-    &ChallengeImpl(day07::Day07),
-    &ChallengeImpl(day08::Day08),
-    &ChallengeImpl(day09::Day09),
-    &ChallengeImpl(day10::Day10),
-    &ChallengeImpl(day11::Day11),
-    &ChallengeImpl(day12::Day12),
-];
+impl<C: Challenge + 'static> ChallengeSolver for ChallengeImpl<C> {
+    fn day(&self) -> u32 {
+        C::DAY_NUMBER
+    }
 
-pub fn solve(day: u32, part: u8) -> Result<String> {
-    let solvers = CHALLENGES
-        .get(day as usize - 1)
-        .context("day out of range")?;
+    fn year(&self) -> u32 {
+        C::YEAR
+    }
 
-    let content = read_file(day)?;
+    fn variants(&self, part: u8) -> Vec<&'static str> {
+        Self::implementations(part)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
 
-    match part {
-        1 => solvers.solve1(&content),
-        2 => solvers.solve2(&content),
-        _ => panic!("Part must be 1 or 2, not {}", part),
+    fn parse(&self, content: &str) -> Timed<Box<dyn ParsedInput>> {
+        Timed::measure(|| match C::parse(content) {
+            Ok(input) => Ok(Box::new(Parsed::<C>(input)) as Box<dyn ParsedInput>),
+            Err(error) => Err(Error::parse(C::DAY_NUMBER, error)),
+        })
     }
-}
 
-pub fn bench(day: u32, part: u8) -> Result<()> {
-    let solvers = CHALLENGES
-        .get(day as usize - 1)
-        .context("day out of range")?;
+    fn bench(&self, content: &str, config: &BenchConfig) -> Result<DayBench, Error> {
+        let (input, parse_alloc) = alloc::measure(|| C::parse(content));
+        let input = input.map_err(|e| Error::parse(C::DAY_NUMBER, e))?;
+        let parse = bench::measure(config, || C::parse(black_box(content)));
+
+        // Only benchmark the variants that succeed
+        let bench_part = |part: u8, part_fn: PartFn<C>| {
+            let (answer, alloc) = alloc::measure(|| part_fn(&input));
+            answer.map_err(|e| Error::solve(C::DAY_NUMBER, part, e))?;
+            Ok((bench::measure(config, || part_fn(black_box(&input))), alloc))
+        };
+
+        let parts = (1..=2)
+            .flat_map(|part| {
+                Self::implementations(part)
+                    .into_iter()
+                    .map(move |(name, part_fn)| (part, name, part_fn))
+            })
+            .map(|(part, name, part_fn)| {
+                let (stats, alloc) = match bench_part(part, part_fn) {
+                    Ok((stats, alloc)) => (Ok(stats), alloc),
+                    Err(error) => (Err(error), None),
+                };
+                PartBench {
+                    part,
+                    variant: name.to_string(),
+                    stats,
+                    alloc,
+                }
+            })
+            .collect();
+
+        Ok(DayBench {
+            day: C::DAY_NUMBER,
+            parse,
+            parse_alloc,
+            parts,
+        })
+    }
+}
 
-    let content = read_file(day)?;
+type Solver = dyn ChallengeSolver + Sync + Send;
+
+/// The events, with the solvers of their days, see [`Registry`].
+///
+/// The inputs and answers of 2020 predate the other years, and are not in per-year locations.
+static EVENTS: &[Event] = &[Event {
+    year: 2020,
+    inputs_dir: "",
+    answers_path: DEFAULT_ANSWERS_PATH,
+    challenges: CHALLENGES,
+}];
+
+/// The solvers of each day of 2020.
+static CHALLENGES: &[(u32, &Solver)] = &[
+    (1, &ChallengeImpl(day01::Day01)),
+    (2, &ChallengeImpl(day02::Day02)),
+    (3, &ChallengeImpl(day03::Day03)),
+    (4, &ChallengeImpl(day04::Day04)),
+    (5, &ChallengeImpl(day05::Day05)),
+    (6, &ChallengeImpl(day06::Day06)),
+    (7, &ChallengeImpl(day07::Day07)),
+    (8, &ChallengeImpl(day08::Day08)),
+    (9, &ChallengeImpl(day09::Day09)),
+    (10, &ChallengeImpl(day10::Day10)),
+    (11, &ChallengeImpl(day11::Day11)),
+    (12, &ChallengeImpl(day12::Day12)),
+];
 
-    match part {
-        1 => bench_part("Part 1", |c| solvers.solve1(c), &content),
-        2 => bench_part("Part 2", |c| solvers.solve2(c), &content),
-        _ => panic!("Part must be 1 or 2, not {}", part),
-    }
+/// The years that have an event, in order.
+pub fn implemented_years() -> Vec<u32> {
+    Registry::global().years().collect()
 }
 
-fn bench_part<F>(name: &str, part: F, content: &str) -> Result<()>
-where
-    F: FnOnce(&str) -> Result<String>,
-{
-    let start = std::time::Instant::now();
-    (part)(content).map(|_| ())?;
-    let elapsed = start.elapsed();
-    println!("{}: {:?}", name, elapsed);
-    Ok(())
+/// The days of a year that have a solver.
+pub fn implemented_days(year: u32) -> Vec<u32> {
+    Registry::global().days(year).collect()
 }
 
-fn read_file(day: u32) -> Result<String> {
-    let file_name = format!("inputs/{:02}.txt", day);
-    let mut file =
-        File::open(&file_name).with_context(|| format!("while opening {}", file_name))?;
+/// Solve a part of a day with the given input content (see [`InputProvider::read`]).
+pub fn solve(year: u32, day: u32, part: u8, content: &str) -> Result<Answer, Error> {
+    let input = Registry::global().get(year, day)?.parse(content).result?;
+
+    input.solve(part, DEFAULT_VARIANT).result
+}
 
-    let mut content = String::new();
-    file.read_to_string(&mut content)?;
+/// Benchmark the parsing and every variant of both parts of a day, on stable Rust.
+///
+/// Each step is warmed up then repeated until the time budget of `config` is spent.
+pub fn bench(year: u32, day: u32, content: &str, config: &BenchConfig) -> Result<DayBench, Error> {
+    let solvers = Registry::global().get(year, day)?;
 
-    Ok(content)
+    solvers.bench(content, config)
 }
 
 #[cfg(all(test, feature = "nightly"))]
@@ -129,14 +309,16 @@ mod benchmarks {
     use test::{black_box, Bencher};
 
     use super::Challenge;
-    use crate::read_file;
+    use crate::{EnvDirectory, InputProvider};
 
     /// Benchmark a part of a challenge:
-    /// - Read the file in inputs/XX.txt (according to read_file)
+    /// - Read the file in inputs/XX.txt (according to EnvDirectory::default)
     /// - Parse it once with C::parse
     /// - Run partX (dependending on the part parameter) on the parsed input N times in the bencher.
     pub fn bench_challenge<C: Challenge>(bencher: &mut Bencher, part: u32) {
-        let content = read_file(C::DAY_NUMBER).expect("Should be able to read input file");
+        let content = EnvDirectory::default()
+            .read(C::YEAR, C::DAY_NUMBER)
+            .expect("Should be able to read input file");
         let input = C::parse(&content).expect("Should parse content");
 
         match part {
@@ -1,19 +1,172 @@
-use anyhow::Result;
+use std::io::{self, BufRead, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+
 use chrono::prelude::*;
 use clap::{self, value_t};
 
-use advent2020::{bench, solve};
+use advent2020::{
+    answers_path, current_revision, default_year, disagreements, is_cached, new_day, parse_days,
+    run_child, session_token, with_all_parse_errors, write_benches, write_reports, Answer,
+    AnswerStore, BenchConfig, CheckSummary, CompareConfig, ComparisonSummary, Error, Fetched,
+    Fetcher, Format, History, HistoryEntry, Input, InputProvider, Isolation, PartReport,
+    PreviousAnswers, Runner, Submitter, Variants, Verdict, WatchSummary, Watcher, BASE_URL_ENV,
+    CHILD_SUBCOMMAND, DEFAULT_BASE_URL, DEFAULT_HISTORY_PATH, DEFAULT_SESSION_PATH,
+    DEFAULT_SUBMISSIONS_PATH,
+};
 
-fn get_today_day() -> u32 {
+const EXIT_CODES_HELP: &str = "EXIT CODES:
+    1    Other errors
+    2    The answers do not match the known answers, the variants disagree, or the
+         submitted answer is wrong
+    3    Day out of range
+    4    Day or year not implemented
+    5    Invalid part
+    6    Input unavailable
+    7    Input could not be parsed
+    8    Part could not be solved
+    9    Unknown variant
+    10   Part timed out (--isolate)
+    11   Part ran out of memory (--isolate)
+    12   Part panicked (--isolate)
+    13   Part killed by a signal (--isolate)
+    14   A benchmarked step is slower than its baseline (--compare)";
+
+/// The day of the event of `year` that is today, if the event is running.
+fn get_today_day(year: u32) -> Result<u32> {
     let today = Local::today();
-    let first_december = NaiveDate::from_ymd(today.year(), 12, 1);
-    let difference = today.naive_local() - first_december;
-    (difference.num_days() + 1) as u32
+    if today.year() as u32 != year || today.month() != 12 {
+        bail!("No day given, and the {} event is not running", year);
+    }
+    Ok(today.day())
+}
+
+/// The year given with `--year`, or the one of the latest event that has started.
+fn get_year(matches: &clap::ArgMatches) -> Result<u32> {
+    if matches.is_present("year") {
+        Ok(value_t!(matches, "year", u32)?)
+    } else {
+        Ok(default_year(Local::today().naive_local()))
+    }
+}
+
+/// The answer store given with `--answers`, or the default one of the year.
+fn get_answers_path(matches: &clap::ArgMatches, year: u32) -> PathBuf {
+    match matches.value_of("answers") {
+        Some(path) => PathBuf::from(path),
+        None => answers_path(year),
+    }
+}
+
+/// The input of the days when `--input` is not given.
+fn get_default_input(matches: &clap::ArgMatches) -> Input {
+    if matches.is_present("disk-inputs") {
+        Input::Disk
+    } else {
+        Input::Default
+    }
+}
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("Error: {:?}", error);
+        process::exit(exit_code(&error));
+    }
+}
+
+/// Map each kind of failure to a distinct exit code, see `EXIT_CODES_HELP`.
+fn exit_code(error: &anyhow::Error) -> i32 {
+    if error.is::<AnswersMismatch>()
+        || error.is::<VariantsDisagree>()
+        || error.is::<AnswerRejected>()
+    {
+        return 2;
+    }
+    if error.is::<BenchRegressed>() {
+        return 14;
+    }
+
+    match error.chain().find_map(|e| e.downcast_ref::<Error>()) {
+        Some(Error::DayOutOfRange(_)) => 3,
+        Some(Error::DayNotImplemented(_)) | Some(Error::YearNotImplemented(_)) => 4,
+        Some(Error::InvalidPart(_)) => 5,
+        Some(Error::InputUnavailable { .. }) => 6,
+        Some(Error::Parse { .. }) => 7,
+        Some(Error::Solve { .. }) => 8,
+        Some(Error::UnknownVariant { .. }) => 9,
+        Some(Error::Timeout { .. }) => 10,
+        Some(Error::OutOfMemory { .. }) => 11,
+        Some(Error::Panic { .. }) => 12,
+        Some(Error::Signal { .. }) => 13,
+        Some(Error::Child { .. }) | None => 1,
+    }
+}
+
+#[derive(Debug)]
+struct AnswersMismatch(usize);
+
+impl std::fmt::Display for AnswersMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} part(s) do not match the known answers", self.0)
+    }
 }
 
-fn main() -> Result<()> {
+impl std::error::Error for AnswersMismatch {}
+
+#[derive(Debug)]
+struct VariantsDisagree(Vec<(u32, u8)>);
+
+impl std::fmt::Display for VariantsDisagree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<_> = self
+            .0
+            .iter()
+            .map(|(day, part)| format!("day {} part {}", day, part))
+            .collect();
+        write!(f, "The variants disagree on {}", parts.join(", "))
+    }
+}
+
+impl std::error::Error for VariantsDisagree {}
+
+#[derive(Debug)]
+struct AnswerRejected(Verdict);
+
+impl std::fmt::Display for AnswerRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The answer is {}", self.0)
+    }
+}
+
+impl std::error::Error for AnswerRejected {}
+
+#[derive(Debug)]
+struct BenchRegressed(usize);
+
+impl std::fmt::Display for BenchRegressed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} step(s) are slower than their baseline", self.0)
+    }
+}
+
+impl std::error::Error for BenchRegressed {}
+
+fn run() -> Result<()> {
     let matches = clap::App::new("Advent 2020")
         .author("Hugo Laloge")
+        .after_help(EXIT_CODES_HELP)
+        .arg(
+            clap::Arg::with_name("year")
+                .short("y")
+                .long("year")
+                .value_name("YEAR")
+                .help("The year of the event [default: the latest implemented one that has started]")
+                .global(true)
+                .takes_value(true),
+        )
         .arg(
             clap::Arg::with_name("day")
                 .short("d")
@@ -21,19 +174,664 @@ fn main() -> Result<()> {
                 .value_name("DAY")
                 .takes_value(true),
         )
-        .arg(clap::Arg::with_name("bench").short("b").long("bench"))
+        .arg(
+            clap::Arg::with_name("days")
+                .long("days")
+                .value_name("DAYS")
+                .help("Run several days, e.g. 1-5,9, and print a summary")
+                .conflicts_with_all(&["day", "all"])
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("all")
+                .short("a")
+                .long("all")
+                .help("Run every implemented day and print a summary")
+                .conflicts_with("day"),
+        )
+        .arg(
+            clap::Arg::with_name("list")
+                .short("l")
+                .long("list")
+                .help("List the implemented days"),
+        )
+        .arg(
+            clap::Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .value_name("PATH")
+                .help("Read the input from PATH, or from stdin if PATH is -")
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("disk-inputs")
+                .long("disk-inputs")
+                .help("Read the inputs from inputs/ (or $AOC_INPUTS) even if they are embedded")
+                .conflicts_with("input")
+                .global(true),
+        )
+        .arg(
+            clap::Arg::with_name("all-errors")
+                .long("all-errors")
+                .help("Report every invalid line of the input instead of the first one"),
+        )
+        .arg(
+            clap::Arg::with_name("part")
+                .short("p")
+                .long("part")
+                .value_name("PART")
+                .help("Only run PART, 1 or 2")
+                .conflicts_with("bench")
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("repeat")
+                .long("repeat")
+                .value_name("N")
+                .help("Run the parsing and the parts N times, and report their fastest run")
+                .conflicts_with("bench")
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("variant")
+                .long("variant")
+                .value_name("NAME")
+                .help("Run the NAME variant of the parts that have one")
+                .conflicts_with_all(&["bench", "cross-check"])
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("cross-check")
+                .long("cross-check")
+                .help("Run every variant of the parts and compare their answers")
+                .conflicts_with_all(&["bench", "check", "record"]),
+        )
+        .arg(
+            clap::Arg::with_name("jobs")
+                .short("j")
+                .long("jobs")
+                .value_name("N")
+                .help("Run the parts on N threads [default: 1]")
+                .conflicts_with("bench")
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("isolate")
+                .long("isolate")
+                .help("Run each part in a child process, to survive panics, hangs and crashes")
+                .conflicts_with("bench"),
+        )
+        .arg(
+            clap::Arg::with_name("timeout")
+                .long("timeout")
+                .value_name("SECS")
+                .help("Kill the isolated parts running longer than SECS seconds [default: 60]")
+                .requires("isolate")
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("memory-limit")
+                .long("memory-limit")
+                .value_name("MB")
+                .help("Limit the memory of the isolated parts to MB megabytes, 0 for no limit [default: 4096]")
+                .requires("isolate")
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("watch")
+                .short("w")
+                .long("watch")
+                .help("Run the days again whenever their input files change, until interrupted")
+                .conflicts_with_all(&["bench", "check", "record", "cross-check"]),
+        )
+        .arg(
+            clap::Arg::with_name("bench")
+                .short("b")
+                .long("bench")
+                .help("Benchmark the parsing and both parts"),
+        )
+        .arg(
+            clap::Arg::with_name("budget")
+                .long("budget")
+                .value_name("MS")
+                .help("Time budget of each benchmarked step, in milliseconds")
+                .requires("bench")
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("history")
+                .long("history")
+                .value_name("PATH")
+                .help("The file the benchmarks are appended to")
+                .default_value(DEFAULT_HISTORY_PATH)
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("compare")
+                .long("compare")
+                .help("Flag the benchmarked steps slower than in the history")
+                .requires("bench"),
+        )
+        .arg(
+            clap::Arg::with_name("threshold")
+                .long("threshold")
+                .value_name("PCT")
+                .help("Slowdown of the median tolerated by --compare, in percent [default: 5]")
+                .requires("compare")
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("baseline")
+                .long("baseline")
+                .value_name("REV")
+                .help("Compare with the benchmarks of REV rather than the latest ones")
+                .requires("compare")
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("format")
+                .short("f")
+                .long("format")
+                .value_name("FORMAT")
+                .help("Output format")
+                .possible_values(&["text", "json", "csv", "quiet"])
+                .default_value("text")
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("check")
+                .long("check")
+                .help("Compare the answers with the known answers")
+                .conflicts_with("bench"),
+        )
+        .arg(
+            clap::Arg::with_name("record")
+                .long("record")
+                .help("Save the answers as the known answers")
+                .conflicts_with_all(&["bench", "check"]),
+        )
+        .arg(
+            clap::Arg::with_name("answers")
+                .long("answers")
+                .value_name("PATH")
+                .help("The file of known answers [default: answers.toml for 2020, answers/YEAR.toml for the other years]")
+                .global(true)
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("session-file")
+                .long("session-file")
+                .value_name("PATH")
+                .help("The session token file, to use the site if AOC_SESSION is unset")
+                .default_value(DEFAULT_SESSION_PATH)
+                .global(true)
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("base-url")
+                .long("base-url")
+                .value_name("URL")
+                .help("The site to download the inputs from and submit the answers to")
+                .env(BASE_URL_ENV)
+                .default_value(DEFAULT_BASE_URL)
+                .global(true)
+                .takes_value(true),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("fetch")
+                .about("Download the inputs of some days into inputs/ (or $AOC_INPUTS), unless already there")
+                .arg(
+                    clap::Arg::with_name("DAYS")
+                        .help("The days to download, e.g. 1-5,9")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("submit")
+                .about("Submit the answer of a part, and save it in the known answers if correct")
+                .arg(clap::Arg::with_name("DAY").required(true))
+                .arg(clap::Arg::with_name("PART").required(true))
+                .arg(
+                    clap::Arg::with_name("ANSWER")
+                        .help("The answer to submit [default: the answer computed on inputs/]"),
+                )
+                .arg(
+                    clap::Arg::with_name("submissions")
+                        .long("submissions")
+                        .value_name("PATH")
+                        .help("The log of the submissions, to not repeat wrong answers")
+                        .default_value(DEFAULT_SUBMISSIONS_PATH)
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("new-day")
+                .about("Generate the module of a day from src/dayXX.rs and register it")
+                .arg(clap::Arg::with_name("DAY").required(true)),
+        )
+        .subcommand(
+            clap::SubCommand::with_name(CHILD_SUBCOMMAND)
+                .setting(clap::AppSettings::Hidden)
+                .about("Run a part on the input read from stdin, and print a JSON report")
+                .arg(clap::Arg::with_name("YEAR").required(true))
+                .arg(clap::Arg::with_name("DAY").required(true))
+                .arg(clap::Arg::with_name("PART").required(true))
+                .arg(clap::Arg::with_name("VARIANT").required(true))
+                .arg(clap::Arg::with_name("all-errors").long("all-errors"))
+                .arg(
+                    clap::Arg::with_name("repetitions")
+                        .long("repetitions")
+                        .default_value("1")
+                        .takes_value(true),
+                ),
+        )
         .get_matches();
 
-    let day = value_t!(matches, "day", u32).unwrap_or_else(|_| get_today_day());
+    if let Some(matches) = matches.subcommand_matches(CHILD_SUBCOMMAND) {
+        let year = value_t!(matches, "YEAR", u32)?;
+        let day = value_t!(matches, "DAY", u32)?;
+        let part = value_t!(matches, "PART", u8)?;
+        let variant = matches.value_of("VARIANT").unwrap();
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+
+        let repetitions = value_t!(matches, "repetitions", usize)?;
+
+        let run = || run_child(year, day, part, variant, &content, repetitions);
+        let report = if matches.is_present("all-errors") {
+            with_all_parse_errors(run)
+        } else {
+            run()
+        };
+        println!("{}", report);
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("new-day") {
+        if matches.is_present("year") && value_t!(matches, "year", u32)? != 2020 {
+            bail!("new-day only generates the days of 2020");
+        }
+        let day = value_t!(matches, "DAY", u32)?;
+        for path in new_day(Path::new("."), day)? {
+            println!("Wrote {}", path.display());
+        }
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("fetch") {
+        let fetcher = fetcher(matches, get_year(matches)?)?;
+        for day in parse_days(matches.value_of("DAYS").unwrap())? {
+            report_fetched(&fetcher.fetch(day)?);
+        }
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("submit") {
+        return run_submit(matches);
+    }
+
+    let year = get_year(&matches)?;
+    if matches.is_present("list") {
+        for day in advent2020::implemented_days(year) {
+            println!("{}", day);
+        }
+        return Ok(());
+    }
+
+    let input = matches
+        .value_of("input")
+        .map_or_else(|| get_default_input(&matches), Input::from_arg);
     let do_bench = matches.is_present("bench");
+    let mut bench_config = BenchConfig::default();
+    if matches.is_present("budget") {
+        bench_config.budget = Duration::from_millis(value_t!(matches, "budget", u64)?);
+    }
+    let history_path = Path::new(matches.value_of("history").unwrap());
+    let compare_config = if matches.is_present("compare") {
+        let mut config = CompareConfig {
+            baseline: matches.value_of("baseline").map(str::to_string),
+            ..CompareConfig::default()
+        };
+        if matches.is_present("threshold") {
+            config.threshold = value_t!(matches, "threshold", f64)?;
+        }
+        Some(config)
+    } else {
+        None
+    };
+
+    let format = value_t!(matches, "format", Format)?;
+    // Rejected before the benchmarks run, rather than when writing them
+    if do_bench && format == Format::Quiet {
+        bail!("--format quiet cannot be used with --bench");
+    }
+
+    let days = if matches.is_present("all") {
+        advent2020::implemented_days(year)
+    } else if let Some(days) = matches.value_of("days") {
+        parse_days(days)?
+    } else {
+        vec![value_t!(matches, "day", u32).map_or_else(|_| get_today_day(year), Ok)?]
+    };
+
+    let per_day = matches!(input, Input::Default | Input::Disk);
+    if days.len() > 1 && !per_day {
+        bail!("--input can only be used with a single day");
+    }
+    if per_day {
+        offer_fetch(year, &days, &input, &matches)?;
+    }
+
+    let answers_path = get_answers_path(&matches, year);
+    let jobs = if matches.is_present("jobs") {
+        value_t!(matches, "jobs", usize)?
+    } else {
+        1
+    };
+    if jobs == 0 {
+        bail!("--jobs must be at least 1");
+    }
+    let variants = match matches.value_of("variant") {
+        Some(name) => Variants::Named(name),
+        None => Variants::Default,
+    };
+
+    let parts = match matches.value_of("part") {
+        Some(_) => vec![value_t!(matches, "part", u8)?],
+        None => vec![1, 2],
+    };
+    let repetitions = if matches.is_present("repeat") {
+        value_t!(matches, "repeat", usize)?
+    } else {
+        1
+    };
+    if repetitions == 0 {
+        bail!("--repeat must be at least 1");
+    }
+
+    let mut runner = Runner::new(year)
+        .days(&days)
+        .parts(&parts)
+        .input(input)
+        .variants(variants)
+        .jobs(jobs)
+        .repetitions(repetitions)
+        .all_parse_errors(matches.is_present("all-errors"));
+    if matches.is_present("isolate") {
+        let mut isolation = Isolation::new(std::env::current_exe()?);
+        if matches.is_present("timeout") {
+            isolation.timeout = Duration::from_secs_f64(value_t!(matches, "timeout", f64)?);
+        }
+        if matches.is_present("memory-limit") {
+            let megabytes = value_t!(matches, "memory-limit", u64)?;
+            isolation.memory_limit = Some(megabytes << 20).filter(|&bytes| bytes > 0);
+        }
+        runner = runner.isolation(isolation);
+    }
 
     if do_bench {
-        bench(day, 1)?;
-        bench(day, 2)?;
+        run_benches(
+            &runner,
+            &bench_config,
+            format,
+            history_path,
+            compare_config.as_ref(),
+        )
+    } else if matches.is_present("watch") {
+        run_watch(&runner)
+    } else if matches.is_present("cross-check") {
+        run_cross_check(&runner.variants(Variants::All).run(), format)
+    } else if matches.is_present("check") {
+        run_check(&runner.run(), &answers_path)
+    } else if matches.is_present("record") {
+        run_record(&runner.run(), &answers_path)
     } else {
-        println!("Part 1 result is {}", solve(day, 1)?);
-        println!("Part 2 result is {}", solve(day, 2)?);
+        run_parts(&runner.run(), format)
+    }
+}
+
+fn fetcher(matches: &clap::ArgMatches, year: u32) -> Result<Fetcher> {
+    let mut fetcher = Fetcher::new(
+        session_token(Path::new(matches.value_of("session-file").unwrap()))?,
+        year,
+    );
+    fetcher.base_url = matches.value_of("base-url").unwrap().to_string();
+    Ok(fetcher)
+}
+
+fn run_submit(matches: &clap::ArgMatches) -> Result<()> {
+    let year = get_year(matches)?;
+    let day = value_t!(matches, "DAY", u32)?;
+    let part = value_t!(matches, "PART", u8)?;
+    let answer = match matches.value_of("ANSWER") {
+        Some(answer) => answer.parse::<Answer>()?,
+        None => {
+            let content = get_default_input(matches).read(year, day)?;
+            advent2020::solve(year, day, part, &content)?
+        }
+    };
+
+    let mut submitter = Submitter::new(
+        session_token(Path::new(matches.value_of("session-file").unwrap()))?,
+        year,
+    );
+    submitter.base_url = matches.value_of("base-url").unwrap().to_string();
+    let response = submitter.submit(
+        Path::new(matches.value_of("submissions").unwrap()),
+        &get_answers_path(matches, year),
+        day,
+        part,
+        &answer,
+    )?;
+
+    println!(
+        "Day {} part {}: {} is {}",
+        day, part, answer, response.verdict
+    );
+    if let Some(cooldown) = response.cooldown {
+        println!("Wait {}s before submitting again", cooldown.as_secs());
+    }
+
+    match response.verdict {
+        Verdict::Correct | Verdict::AlreadySolved => Ok(()),
+        Verdict::Wait => bail!("The answer was not checked, submit it again later"),
+        verdict => Err(AnswerRejected(verdict).into()),
+    }
+}
+
+fn report_fetched(fetched: &Fetched) {
+    match fetched {
+        Fetched::Cached(path) => println!("{} is already there", path.display()),
+        Fetched::Downloaded(path) => println!("Wrote {}", path.display()),
     }
+}
+
+/// Offer to download the missing inputs of the implemented days, when run in a terminal.
+fn offer_fetch(year: u32, days: &[u32], input: &Input, matches: &clap::ArgMatches) -> Result<()> {
+    let implemented = advent2020::implemented_days(year);
+    let missing: Vec<_> = days
+        .iter()
+        .copied()
+        .filter(|&day| {
+            // The inputs not read from files, such as the embedded ones, are never missing
+            implemented.contains(&day)
+                && input.path(year, day).is_some_and(|path| !is_cached(&path))
+        })
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let list = missing
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+    if !io::stdin().is_terminal() {
+        eprintln!(
+            "Missing inputs, `fetch --year {} {}` downloads them",
+            year, list
+        );
+        return Ok(());
+    }
+
+    eprint!(
+        "The inputs of days {} are missing, download them? [y/N] ",
+        list
+    );
+    let mut reply = String::new();
+    io::stdin().lock().read_line(&mut reply)?;
+    if !reply.trim().eq_ignore_ascii_case("y") {
+        return Ok(());
+    }
+
+    let fetcher = fetcher(matches, year)?;
+    for day in missing {
+        report_fetched(&fetcher.fetch(day)?);
+    }
+    Ok(())
+}
+
+fn run_check(reports: &[PartReport], answers_path: &Path) -> Result<()> {
+    let store = AnswerStore::load(answers_path)?;
+    let checks = store.check(reports);
+    print!("{}", CheckSummary(&checks));
+
+    check_failures(reports)?;
+
+    let mismatches = checks.iter().filter(|c| c.status.is_regression()).count();
+    if mismatches > 0 {
+        return Err(AnswersMismatch(mismatches).into());
+    }
+
+    Ok(())
+}
+
+fn run_record(reports: &[PartReport], answers_path: &Path) -> Result<()> {
+    write_reports(Format::Text, reports, io::stdout().lock())?;
+
+    let mut store = AnswerStore::load(answers_path)?;
+    store.record(reports)?;
+    store.save(answers_path)?;
+
+    check_failures(reports)
+}
+
+fn run_cross_check(reports: &[PartReport], format: Format) -> Result<()> {
+    write_reports(format, reports, io::stdout().lock())?;
+
+    check_failures(reports)?;
+
+    let disagreements = disagreements(reports);
+    if !disagreements.is_empty() {
+        return Err(VariantsDisagree(disagreements).into());
+    }
+
+    Ok(())
+}
+
+fn run_parts(reports: &[PartReport], format: Format) -> Result<()> {
+    write_reports(format, reports, io::stdout().lock())?;
+
+    if format == Format::Quiet {
+        for report in reports {
+            if let Err(error) = &report.answer {
+                eprintln!("Day {} part {} failed: {}", report.day, report.part, error);
+            }
+        }
+    }
+
+    check_failures(reports)
+}
 
+/// Fail with the error of the first failed part, if any, after printing the diagnostics.
+fn check_failures(reports: &[PartReport]) -> Result<()> {
+    print_diagnostics(reports);
+
+    let mut errors = reports.iter().filter_map(|r| r.answer.as_ref().err());
+
+    match errors.next() {
+        Some(first) => Err(anyhow::Error::new(first.clone())
+            .context(format!("{} part(s) failed", 1 + errors.count()))),
+        None => Ok(()),
+    }
+}
+
+/// Print the diagnostics of the parse errors on stderr, once per day.
+fn print_diagnostics(reports: &[PartReport]) {
+    let mut diagnosed_days = Vec::new();
+    for report in reports {
+        if let Err(error) = &report.answer {
+            if let Some(diagnostics) = error.diagnostics(&report.input) {
+                if !diagnosed_days.contains(&report.day) {
+                    eprintln!("{}", diagnostics);
+                    diagnosed_days.push(report.day);
+                }
+            }
+        }
+    }
+}
+
+/// Run the days, then again whenever their input files change, showing the errors inline.
+fn run_watch(runner: &Runner) -> Result<()> {
+    let paths = runner
+        .input_paths()
+        .ok_or_else(|| anyhow!("--watch needs input files, not stdin or embedded inputs"))?;
+    let mut watcher = Watcher::new(paths);
+    let mut previous = PreviousAnswers::default();
+
+    loop {
+        let reports = runner.run();
+        let changes = previous.update(&reports);
+        println!("[{}]", Local::now().format("%H:%M:%S"));
+        print!("{}", WatchSummary(&reports, &changes));
+        print_diagnostics(&reports);
+
+        let paths: Vec<_> = watcher.paths().map(|p| p.display().to_string()).collect();
+        println!("Watching {} for changes\n", paths.join(", "));
+        watcher.wait();
+    }
+}
+
+/// Run the benchmarks and append them to the history, after comparing them with it if asked.
+fn run_benches(
+    runner: &Runner,
+    config: &BenchConfig,
+    format: Format,
+    history_path: &Path,
+    compare: Option<&CompareConfig>,
+) -> Result<()> {
+    let benches = runner.bench(config)?;
+
+    write_benches(format, &benches, io::stdout().lock())?;
+
+    let revision = current_revision();
+    let now = Utc::now();
+    let entries: Vec<_> = benches
+        .iter()
+        .flat_map(|(input, bench)| HistoryEntry::from_bench(input, bench, revision.as_deref(), now))
+        .collect();
+
+    let mut regressions = 0;
+    if let Some(compare) = compare {
+        let comparisons = History::load(history_path)?.compare(&entries, compare);
+        let summary = ComparisonSummary(&comparisons);
+        // Keep the output parseable in the other formats
+        if format == Format::Text {
+            print!("{}", summary);
+        } else {
+            eprint!("{}", summary);
+        }
+        regressions = comparisons
+            .iter()
+            .filter(|c| c.status.is_regression())
+            .count();
+    }
+
+    History::append(history_path, &entries)?;
+
+    if regressions > 0 {
+        return Err(BenchRegressed(regressions).into());
+    }
     Ok(())
 }
@@ -0,0 +1,302 @@
+use std::io::Write;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use crate::runner::part_label;
+use crate::{Answer, DayBench, Error, PartReport, Stats, Summary};
+
+/// How results are written on the standard output.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// Human-readable text.
+    Text,
+    /// A JSON array of records.
+    Json,
+    /// CSV records, with a header.
+    Csv,
+    /// Only the answers, one per line.
+    Quiet,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "quiet" => Ok(Format::Quiet),
+            _ => Err(anyhow!("Unknown format {}", s)),
+        }
+    }
+}
+
+/// The record of a part, as written in JSON and CSV.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Record {
+    pub day: u32,
+    pub part: u8,
+    pub variant: String,
+    pub answer: Option<Answer>,
+    pub parse_time_ns: Option<u128>,
+    pub solve_time_ns: Option<u128>,
+    pub parse_allocs: Option<u64>,
+    pub parse_alloc_bytes: Option<u64>,
+    pub parse_peak_bytes: Option<u64>,
+    pub solve_allocs: Option<u64>,
+    pub solve_alloc_bytes: Option<u64>,
+    pub solve_peak_bytes: Option<u64>,
+    /// The number of parts that were run at the same time.
+    pub jobs: usize,
+    pub error: Option<String>,
+    pub input: String,
+}
+
+impl From<&PartReport> for Record {
+    fn from(report: &PartReport) -> Self {
+        Self {
+            day: report.day,
+            part: report.part,
+            variant: report.variant.clone(),
+            answer: report.answer.as_ref().ok().cloned(),
+            parse_time_ns: report.parse_time.as_ref().map(Duration::as_nanos),
+            solve_time_ns: report.solve_time.as_ref().map(Duration::as_nanos),
+            parse_allocs: report.parse_alloc.map(|a| a.allocations),
+            parse_alloc_bytes: report.parse_alloc.map(|a| a.bytes),
+            parse_peak_bytes: report.parse_alloc.map(|a| a.peak_bytes),
+            solve_allocs: report.solve_alloc.map(|a| a.allocations),
+            solve_alloc_bytes: report.solve_alloc.map(|a| a.bytes),
+            solve_peak_bytes: report.solve_alloc.map(|a| a.peak_bytes),
+            jobs: report.jobs,
+            error: report.answer.as_ref().err().map(Error::to_string),
+            input: report.input.clone(),
+        }
+    }
+}
+
+/// The record of a benchmarked step, as written in JSON and CSV.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct BenchRecord {
+    pub day: u32,
+    /// `parse`, `part1` or `part2`.
+    pub step: &'static str,
+    /// The variant of the part, `None` for the parsing.
+    pub variant: Option<String>,
+    pub samples: Option<usize>,
+    pub min_ns: Option<u128>,
+    pub median_ns: Option<u128>,
+    pub mean_ns: Option<u128>,
+    pub p95_ns: Option<u128>,
+    pub stddev_ns: Option<u128>,
+    /// The allocations of a single run.
+    pub allocs: Option<u64>,
+    pub alloc_bytes: Option<u64>,
+    pub peak_bytes: Option<u64>,
+    pub error: Option<String>,
+    pub input: String,
+}
+
+impl BenchRecord {
+    pub fn from_bench(input: &str, bench: &DayBench) -> Vec<Self> {
+        let parts = bench.parts.iter().map(|part| {
+            let step = if part.part == 1 { "part1" } else { "part2" };
+            (
+                step,
+                Some(part.variant.clone()),
+                part.stats.as_ref(),
+                part.alloc,
+            )
+        });
+
+        std::iter::once(("parse", None, Ok(&bench.parse), bench.parse_alloc))
+            .chain(parts)
+            .map(|(step, variant, stats, alloc)| {
+                let ok = stats.as_ref().ok();
+                let field = |f: fn(&Stats) -> Duration| ok.map(|s| f(s).as_nanos());
+                Self {
+                    day: bench.day,
+                    step,
+                    variant,
+                    samples: ok.map(|s| s.samples),
+                    min_ns: field(|s| s.min),
+                    median_ns: field(|s| s.median),
+                    mean_ns: field(|s| s.mean),
+                    p95_ns: field(|s| s.p95),
+                    stddev_ns: field(|s| s.stddev),
+                    allocs: alloc.map(|a| a.allocations),
+                    alloc_bytes: alloc.map(|a| a.bytes),
+                    peak_bytes: alloc.map(|a| a.peak_bytes),
+                    error: stats.as_ref().err().map(|e| e.to_string()),
+                    input: input.to_string(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Write the reports of some parts in the given format.
+pub fn write_reports<W: Write>(format: Format, reports: &[PartReport], mut w: W) -> Result<()> {
+    match format {
+        Format::Text if is_single_day(reports) => {
+            for report in reports {
+                match &report.answer {
+                    Ok(answer @ Answer::Block(_)) => {
+                        writeln!(w, "Part {} result is\n{}", label(report), answer)?
+                    }
+                    Ok(answer) => writeln!(w, "Part {} result is {}", label(report), answer)?,
+                    Err(error) => writeln!(w, "Part {} failed: {}", label(report), error)?,
+                }
+            }
+        }
+        Format::Text => write!(w, "{}", Summary(reports))?,
+        Format::Json => write_json(reports.iter().map(Record::from), w)?,
+        Format::Csv => write_csv(reports.iter().map(Record::from), w)?,
+        Format::Quiet => {
+            for answer in reports.iter().filter_map(|r| r.answer.as_ref().ok()) {
+                writeln!(w, "{}", answer)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Write benchmarks, with the input they were run on, in the given format.
+pub fn write_benches<W: Write>(
+    format: Format,
+    benches: &[(String, DayBench)],
+    mut w: W,
+) -> Result<()> {
+    let records = || {
+        benches
+            .iter()
+            .flat_map(|(input, bench)| BenchRecord::from_bench(input, bench))
+    };
+
+    match format {
+        Format::Text => {
+            for (_, bench) in benches {
+                writeln!(w, "{}", bench)?;
+            }
+        }
+        Format::Json => write_json(records(), w)?,
+        Format::Csv => write_csv(records(), w)?,
+        Format::Quiet => return Err(anyhow!("The quiet format cannot be used for benchmarks")),
+    }
+
+    Ok(())
+}
+
+fn label(report: &PartReport) -> String {
+    part_label(report.part, &report.variant)
+}
+
+fn is_single_day(reports: &[PartReport]) -> bool {
+    reports.iter().all(|r| r.day == reports[0].day)
+}
+
+fn write_json<T: Serialize, W: Write>(records: impl Iterator<Item = T>, mut w: W) -> Result<()> {
+    serde_json::to_writer_pretty(&mut w, &records.collect::<Vec<_>>())?;
+    writeln!(w)?;
+    Ok(())
+}
+
+fn write_csv<T: Serialize, W: Write>(records: impl Iterator<Item = T>, w: W) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(w);
+    for record in records {
+        writer.serialize(record)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reports() -> Vec<PartReport> {
+        vec![
+            PartReport {
+                day: 1,
+                part: 1,
+                variant: crate::DEFAULT_VARIANT.to_string(),
+                input: "inputs/01.txt".to_string(),
+                answer: Ok(Answer::Integer(42)),
+                parse_time: Some(Duration::from_nanos(10)),
+                solve_time: Some(Duration::from_nanos(20)),
+                parse_alloc: None,
+                solve_alloc: None,
+                shared_parse: true,
+                jobs: 1,
+            },
+            PartReport {
+                day: 1,
+                part: 2,
+                variant: crate::DEFAULT_VARIANT.to_string(),
+                input: "inputs/01.txt".to_string(),
+                answer: Err(Error::solve(
+                    1,
+                    2,
+                    anyhow!("Could not find combination, sorry"),
+                )),
+                parse_time: Some(Duration::from_nanos(10)),
+                solve_time: None,
+                parse_alloc: None,
+                solve_alloc: None,
+                shared_parse: true,
+                jobs: 1,
+            },
+        ]
+    }
+
+    fn written(format: Format) -> String {
+        let mut out = Vec::new();
+        write_reports(format, &reports(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("json".parse::<Format>().unwrap(), Format::Json);
+        assert!("xml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn test_write_text() {
+        assert_eq!(
+            written(Format::Text),
+            "Part 1 result is 42\nPart 2 failed: Could not find combination, sorry\n"
+        );
+    }
+
+    #[test]
+    fn test_write_quiet() {
+        assert_eq!(written(Format::Quiet), "42\n");
+    }
+
+    #[test]
+    fn test_write_csv() {
+        assert_eq!(
+            written(Format::Csv),
+            "\
+day,part,variant,answer,parse_time_ns,solve_time_ns,parse_allocs,parse_alloc_bytes,parse_peak_bytes,solve_allocs,solve_alloc_bytes,solve_peak_bytes,jobs,error,input
+1,1,default,42,10,20,,,,,,,1,,inputs/01.txt
+1,2,default,,10,,,,,,,,1,\"Could not find combination, sorry\",inputs/01.txt
+"
+        );
+    }
+
+    #[test]
+    fn test_write_json() {
+        let records: serde_json::Value = serde_json::from_str(&written(Format::Json)).unwrap();
+        assert_eq!(records[0]["answer"], 42);
+        assert_eq!(records[0]["solve_time_ns"], 20);
+        assert_eq!(records[1]["answer"], serde_json::Value::Null);
+        assert_eq!(records[1]["error"], "Could not find combination, sorry");
+        assert_eq!(records[1]["input"], "inputs/01.txt");
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Result};
+use chrono::{Datelike as _, NaiveDate};
+
+use crate::{Error, Solver, EVENTS};
+
+/// The days of an Advent calendar.
+pub const DAYS: std::ops::RangeInclusive<u32> = 1..=25;
+
+/// The solvers of the days of a year, and where the files of the year are.
+pub(crate) struct Event {
+    pub year: u32,
+    /// The directory of the `NN.txt` inputs, relative to the root of the inputs.
+    pub inputs_dir: &'static str,
+    /// The default answer store of the year.
+    pub answers_path: &'static str,
+    pub challenges: &'static [(u32, &'static Solver)],
+}
+
+/// The solvers, indexed by their declared year and day.
+pub(crate) struct Registry {
+    solvers: BTreeMap<(u32, u32), &'static Solver>,
+    events: BTreeMap<u32, &'static Event>,
+}
+
+impl Registry {
+    /// Build a registry from the events and their `(day, solver)` entries.
+    ///
+    /// Fails if a year or a day is registered twice, or if a day is outside of the calendar, or
+    /// does not match the `YEAR` and `DAY_NUMBER` of its solver.
+    pub fn new(events: &'static [Event]) -> Result<Self> {
+        let mut solvers = BTreeMap::new();
+        let mut by_year = BTreeMap::new();
+
+        for event in events {
+            if by_year.insert(event.year, event).is_some() {
+                return Err(anyhow!("Year {} is registered twice", event.year));
+            }
+
+            for &(day, solver) in event.challenges {
+                if !DAYS.contains(&day) {
+                    return Err(anyhow!("Registered day {} is out of range", day));
+                }
+                if (solver.year(), solver.day()) != (event.year, day) {
+                    return Err(anyhow!(
+                        "Day {} of {} is registered with the solver of day {} of {}",
+                        day,
+                        event.year,
+                        solver.day(),
+                        solver.year()
+                    ));
+                }
+                if solvers.insert((event.year, day), solver).is_some() {
+                    return Err(anyhow!("Day {} of {} is registered twice", day, event.year));
+                }
+            }
+        }
+
+        Ok(Self {
+            solvers,
+            events: by_year,
+        })
+    }
+
+    /// The registry of all the challenges of the crate.
+    pub fn global() -> &'static Self {
+        static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+        REGISTRY.get_or_init(|| Registry::new(EVENTS).expect("Invalid challenge registry"))
+    }
+
+    pub fn get(&self, year: u32, day: u32) -> Result<&'static Solver, Error> {
+        if !DAYS.contains(&day) {
+            return Err(Error::DayOutOfRange(day));
+        }
+        if !self.events.contains_key(&year) {
+            return Err(Error::YearNotImplemented(year));
+        }
+
+        self.solvers
+            .get(&(year, day))
+            .copied()
+            .ok_or(Error::DayNotImplemented(day))
+    }
+
+    /// The years that have an event, in order.
+    pub fn years(&self) -> impl Iterator<Item = u32> + '_ {
+        self.events.keys().copied()
+    }
+
+    /// The implemented days of a year, in order.
+    pub fn days(&self, year: u32) -> impl Iterator<Item = u32> + '_ {
+        self.solvers
+            .range((year, 0)..=(year, u32::MAX))
+            .map(|(&(_, day), _)| day)
+    }
+}
+
+/// The latest implemented year whose event has started on `today`, or the first one.
+pub fn default_year(today: NaiveDate) -> u32 {
+    let latest_event = if today.month() == 12 {
+        today.year()
+    } else {
+        today.year() - 1
+    };
+    let years: Vec<_> = Registry::global().years().collect();
+
+    years
+        .iter()
+        .rev()
+        .copied()
+        .find(|&year| year as i32 <= latest_event)
+        .unwrap_or(years[0])
+}
+
+/// The directory of the inputs of a year, relative to the root of the inputs: `YYYY` unless its
+/// event says otherwise.
+pub(crate) fn inputs_dir(year: u32) -> PathBuf {
+    match Registry::global().events.get(&year) {
+        Some(event) => event.inputs_dir.into(),
+        None => year.to_string().into(),
+    }
+}
+
+/// The default answer store of a year: `answers/YYYY.toml` unless its event says otherwise.
+pub fn answers_path(year: u32) -> PathBuf {
+    match Registry::global().events.get(&year) {
+        Some(event) => event.answers_path.into(),
+        None => PathBuf::from("answers").join(format!("{}.toml", year)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{day01, day02, ChallengeImpl};
+
+    static DAY01: ChallengeImpl<day01::Day01> = ChallengeImpl(day01::Day01);
+    static DAY02: ChallengeImpl<day02::Day02> = ChallengeImpl(day02::Day02);
+
+    /// Leak the events, as a registry only borrows static ones.
+    fn events(events: Vec<(u32, Vec<(u32, &'static Solver)>)>) -> &'static [Event] {
+        let events = events
+            .into_iter()
+            .map(|(year, challenges)| Event {
+                year,
+                inputs_dir: "",
+                answers_path: "answers.toml",
+                challenges: Vec::leak(challenges),
+            })
+            .collect();
+        Vec::leak(events)
+    }
+
+    #[test]
+    fn test_global_registry() {
+        let registry = Registry::global();
+        assert_eq!(registry.years().collect::<Vec<_>>(), vec![2020]);
+        assert_eq!(registry.days(2020).count(), crate::CHALLENGES.len());
+        assert_eq!(registry.get(2020, 2).unwrap().day(), 2);
+    }
+
+    #[test]
+    fn test_lookup_errors() {
+        let registry =
+            Registry::new(events(vec![(2020, vec![(2, &DAY02)]), (2021, vec![])])).unwrap();
+
+        assert_eq!(registry.days(2020).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(registry.days(2021).count(), 0);
+        assert!(matches!(
+            registry.get(2020, 0),
+            Err(Error::DayOutOfRange(0))
+        ));
+        assert!(matches!(
+            registry.get(2020, 1),
+            Err(Error::DayNotImplemented(1))
+        ));
+        assert!(matches!(
+            registry.get(2021, 2),
+            Err(Error::DayNotImplemented(2))
+        ));
+        assert!(matches!(
+            registry.get(2019, 2),
+            Err(Error::YearNotImplemented(2019))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_entries() {
+        assert!(Registry::new(events(vec![(2020, vec![(1, &DAY01), (1, &DAY01)])])).is_err());
+        assert!(Registry::new(events(vec![(2020, vec![(1, &DAY02)])])).is_err());
+        assert!(Registry::new(events(vec![(2020, vec![(26, &DAY01)])])).is_err());
+        assert!(Registry::new(events(vec![(2021, vec![(1, &DAY01)])])).is_err());
+        assert!(Registry::new(events(vec![(2020, vec![]), (2020, vec![])])).is_err());
+        assert!(Registry::new(events(vec![(2020, vec![(2, &DAY02), (1, &DAY01)])])).is_ok());
+    }
+
+    #[test]
+    fn test_default_year() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(default_year(date(2020, 12, 5)), 2020);
+        assert_eq!(default_year(date(2026, 10, 18)), 2020);
+        // Before the first event
+        assert_eq!(default_year(date(2020, 11, 30)), 2020);
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(inputs_dir(2020), PathBuf::new());
+        assert_eq!(inputs_dir(2021), PathBuf::from("2021"));
+        assert_eq!(answers_path(2020), PathBuf::from("answers.toml"));
+        assert_eq!(answers_path(2021), PathBuf::from("answers/2021.toml"));
+    }
+}
@@ -0,0 +1,789 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::isolation::run_days_isolated;
+use crate::registry::Registry;
+use crate::DEFAULT_VARIANT;
+use crate::{alloc, bench, table, utils, AllocStats, Answer, BenchConfig, DayBench, Error};
+use crate::{Input, InputProvider, Isolation, ParsedInput, Solver};
+
+/// The outcome of a single part of a day.
+#[derive(Debug)]
+pub struct PartReport {
+    pub day: u32,
+    pub part: u8,
+    /// The implementation of the part, see [`Challenge::variants`](crate::Challenge::variants).
+    pub variant: String,
+    /// Where the input was read from.
+    pub input: String,
+    pub answer: Result<Answer, Error>,
+    /// The parsing of the input of the day, see `shared_parse`.
+    /// `None` if the input could not be read.
+    pub parse_time: Option<Duration>,
+    /// `None` if the input could not be read or parsed.
+    pub solve_time: Option<Duration>,
+    /// `None` if the input could not be read, or the allocations are not counted.
+    pub parse_alloc: Option<AllocStats>,
+    /// `None` if the input could not be read or parsed, or the allocations are not counted.
+    pub solve_alloc: Option<AllocStats>,
+    /// Is the parsing shared by the parts of the day? It is not when each part runs in its own
+    /// process, see [`Isolation`].
+    pub shared_parse: bool,
+    /// The number of parts that were run at the same time.
+    pub jobs: usize,
+}
+
+impl PartReport {
+    pub fn is_ok(&self) -> bool {
+        self.answer.is_ok()
+    }
+}
+
+/// Which implementations of the parts to run.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Variants<'a> {
+    /// The default implementations.
+    Default,
+    /// The named variant for the parts that have it, and the default one for the others.
+    ///
+    /// The parts fail if none of the selected days has the variant.
+    Named(&'a str),
+    /// Every implementation, to compare their answers.
+    All,
+}
+
+impl<'a> Variants<'a> {
+    /// The implementations to run for a part of a day.
+    ///
+    /// `known` tells whether a selected day has the named variant, see
+    /// [`Runner::variant_known`]: if not, the parts run it anyway, to fail with
+    /// [`Error::UnknownVariant`].
+    pub(crate) fn select(self, solver: &Solver, part: u8, known: bool) -> Vec<&'a str> {
+        match self {
+            Variants::Default => vec![DEFAULT_VARIANT],
+            Variants::Named(name) if !known || solver.variants(part).contains(&name) => {
+                vec![name]
+            }
+            Variants::Named(_) => vec![DEFAULT_VARIANT],
+            Variants::All => solver.variants(part),
+        }
+    }
+}
+
+/// Runs the parts of some days, and returns their reports without printing anything.
+///
+/// The days are run without stopping at the first failure, see [`PartReport::answer`].
+///
+/// ```
+/// use advent2020::{InMemory, Runner};
+///
+/// let reports = Runner::new(2020)
+///     .days(&[1])
+///     .parts(&[1])
+///     .input(InMemory::new().with(2020, 1, "1721\n979\n366\n299\n675\n1456\n"))
+///     .run();
+/// assert_eq!(reports[0].answer.as_ref().unwrap().to_string(), "514579");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Runner<'a> {
+    pub(crate) year: u32,
+    pub(crate) days: Vec<u32>,
+    pub(crate) parts: Vec<u8>,
+    pub(crate) input: Arc<dyn InputProvider>,
+    pub(crate) variants: Variants<'a>,
+    pub(crate) jobs: usize,
+    pub(crate) repetitions: usize,
+    all_parse_errors: bool,
+    isolation: Option<Isolation>,
+}
+
+impl<'a> Runner<'a> {
+    /// A runner of both parts of every implemented day of `year`, on the default inputs.
+    pub fn new(year: u32) -> Self {
+        Self {
+            year,
+            days: crate::implemented_days(year),
+            parts: vec![1, 2],
+            input: Arc::new(Input::Default),
+            variants: Variants::Default,
+            jobs: 1,
+            repetitions: 1,
+            all_parse_errors: false,
+            isolation: None,
+        }
+    }
+
+    pub fn days(mut self, days: &[u32]) -> Self {
+        self.days = days.to_vec();
+        self
+    }
+
+    pub fn parts(mut self, parts: &[u8]) -> Self {
+        self.parts = parts.to_vec();
+        self
+    }
+
+    /// Where to read the inputs from, [`Input::Default`] by default.
+    pub fn input(mut self, input: impl InputProvider + 'static) -> Self {
+        self.input = Arc::new(input);
+        self
+    }
+
+    pub fn variants(mut self, variants: Variants<'a>) -> Self {
+        self.variants = variants;
+        self
+    }
+
+    /// Run the days, then the parts, on `jobs` threads. The timings may include contention.
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs.max(1);
+        self
+    }
+
+    /// Run the parsing and each part `repetitions` times, and report their fastest run.
+    pub fn repetitions(mut self, repetitions: usize) -> Self {
+        self.repetitions = repetitions.max(1);
+        self
+    }
+
+    /// Make the parsers report every invalid line instead of the first one.
+    pub fn all_parse_errors(mut self, all_parse_errors: bool) -> Self {
+        self.all_parse_errors = all_parse_errors;
+        self
+    }
+
+    /// Run each part in a child process, see [`Isolation`].
+    pub fn isolation(mut self, isolation: Isolation) -> Self {
+        self.isolation = Some(isolation);
+        self
+    }
+
+    /// Run the parts, and return their reports in day, part and variant order.
+    pub fn run(&self) -> Vec<PartReport> {
+        self.run_with(|_| {})
+    }
+
+    /// Like [`run`](Self::run), but also give each report to `sink` as soon as its part is
+    /// done, in the order the parts finish.
+    pub fn run_with(&self, sink: impl FnMut(&PartReport) + Send) -> Vec<PartReport> {
+        let sink = Mutex::new(sink);
+        let notify = |report: &PartReport| (sink.lock().expect("A sink panicked"))(report);
+
+        self.with_parse_errors(|| match &self.isolation {
+            Some(isolation) => run_days_isolated(self, isolation, &notify),
+            None => run_days(self, &notify),
+        })
+    }
+
+    /// Benchmark the parsing and every variant of both parts of the days, see [`bench`].
+    ///
+    /// Return the benchmarks with the input they were run on, or the first failure.
+    pub fn bench(&self, config: &BenchConfig) -> Result<Vec<(String, DayBench)>, Error> {
+        self.with_parse_errors(|| {
+            self.days
+                .iter()
+                .map(|&day| {
+                    let content = self.input.read(self.year, day)?;
+                    let bench = bench(self.year, day, &content, config)?;
+                    Ok((self.input.describe(self.year, day), bench))
+                })
+                .collect()
+        })
+    }
+
+    /// The files the inputs of the days are read from, unless they are not all files.
+    pub fn input_paths(&self) -> Option<Vec<PathBuf>> {
+        self.days
+            .iter()
+            .map(|&day| self.input.path(self.year, day))
+            .collect()
+    }
+
+    /// Does a selected part of a selected day have the named variant, if any?
+    ///
+    /// Checked once before running, so that the days without the variant run their default
+    /// implementations.
+    pub(crate) fn variant_known(&self) -> bool {
+        let name = match self.variants {
+            Variants::Named(name) => name,
+            Variants::Default | Variants::All => return true,
+        };
+        self.days
+            .iter()
+            .filter_map(|&day| Registry::global().get(self.year, day).ok())
+            .any(|solver| {
+                self.parts
+                    .iter()
+                    .any(|&part| solver.variants(part).contains(&name))
+            })
+    }
+
+    fn with_parse_errors<T>(&self, f: impl FnOnce() -> T) -> T {
+        if self.all_parse_errors {
+            utils::with_all_parse_errors(f)
+        } else {
+            f()
+        }
+    }
+}
+
+/// Run the selected parts of every day of the runner.
+///
+/// The input of each day is read and parsed once, then shared by its parts.
+/// The days are parsed, then the parts run, on `jobs` threads, and the reports are in day order.
+/// Each step is timed on its own, but the timings may include contention if `jobs` > 1.
+fn run_days(runner: &Runner, notify: &(dyn Fn(&PartReport) + Sync)) -> Vec<PartReport> {
+    let collect_all_errors = utils::collecting_all_errors();
+    let Runner {
+        year,
+        ref input,
+        variants,
+        jobs,
+        repetitions,
+        ..
+    } = *runner;
+
+    let prepared = map_parallel(&runner.days, jobs, |&day| {
+        let prepare = || -> Result<_, Error> {
+            let solver = Registry::global().get(year, day)?;
+            let content = input.read(year, day)?;
+            let parse = || Timed::fastest(repetitions, || solver.parse(&content));
+            let parsed = if collect_all_errors {
+                utils::with_all_parse_errors(parse)
+            } else {
+                parse()
+            };
+            Ok((solver, parsed))
+        };
+        PreparedDay {
+            day,
+            origin: input.describe(year, day),
+            prepared: prepare(),
+        }
+    });
+
+    let known = runner.variant_known();
+    let tasks: Vec<Task> = prepared
+        .iter()
+        .flat_map(|day| {
+            runner.parts.iter().flat_map(move |&part| {
+                let variants = match &day.prepared {
+                    Ok((solver, _)) => variants.select(*solver, part, known),
+                    Err(_) => vec![DEFAULT_VARIANT],
+                };
+                variants
+                    .into_iter()
+                    .map(move |variant| Task { day, part, variant })
+            })
+        })
+        .collect();
+
+    let jobs = jobs.clamp(1, tasks.len().max(1));
+
+    map_parallel(&tasks, jobs, |task| {
+        let (parse, solve) = match &task.day.prepared {
+            Ok((_, parse)) => {
+                let solve = match &parse.result {
+                    Ok(parsed) => {
+                        Timed::fastest(repetitions, || parsed.solve(task.part, task.variant))
+                    }
+                    Err(error) => Timed::skipped(error.clone()),
+                };
+                (Some(parse), solve)
+            }
+            Err(error) => (None, Timed::skipped(error.clone())),
+        };
+
+        let report = PartReport {
+            day: task.day.day,
+            part: task.part,
+            variant: task.variant.to_string(),
+            input: task.day.origin.clone(),
+            answer: solve.result,
+            parse_time: parse.and_then(|p| p.time),
+            solve_time: solve.time,
+            parse_alloc: parse.and_then(|p| p.alloc),
+            solve_alloc: solve.alloc,
+            shared_parse: true,
+            jobs,
+        };
+        notify(&report);
+        report
+    })
+}
+
+/// A day whose input was read and parsed, or why it could not be.
+struct PreparedDay {
+    day: u32,
+    origin: String,
+    prepared: Result<(&'static Solver, ParseStep), Error>,
+}
+
+type ParseStep = Timed<Box<dyn ParsedInput>>;
+
+/// A part to run on the parsed input of its day.
+struct Task<'a> {
+    day: &'a PreparedDay,
+    part: u8,
+    variant: &'a str,
+}
+
+/// Apply `f` to every item on `jobs` threads, and return the results in order.
+pub(crate) fn map_parallel<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    if jobs <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, R)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let idx = next.fetch_add(1, Ordering::Relaxed);
+                        match items.get(idx) {
+                            Some(item) => results.push((idx, f(item))),
+                            None => break results,
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("A worker panicked"))
+            .collect()
+    });
+
+    results.sort_unstable_by_key(|(idx, _)| *idx);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// The days and parts whose variants found different answers.
+pub fn disagreements(reports: &[PartReport]) -> Vec<(u32, u8)> {
+    let mut disagreements = Vec::new();
+
+    for report in reports {
+        let key = (report.day, report.part);
+        let first_answer = reports
+            .iter()
+            .filter(|other| (other.day, other.part) == key)
+            .find_map(|other| other.answer.as_ref().ok());
+
+        if let (Ok(answer), Some(first_answer)) = (&report.answer, first_answer) {
+            if answer != first_answer && !disagreements.contains(&key) {
+                disagreements.push(key);
+            }
+        }
+    }
+
+    disagreements
+}
+
+/// The part number, followed by the variant if it is not the default one.
+pub(crate) fn part_label(part: u8, variant: &str) -> String {
+    if variant == DEFAULT_VARIANT {
+        part.to_string()
+    } else {
+        format!("{} ({})", part, variant)
+    }
+}
+
+/// The outcome of a step of a challenge, with its duration and allocations.
+pub(crate) struct Timed<T> {
+    pub result: Result<T, Error>,
+    /// `None` if the step was not run.
+    pub time: Option<Duration>,
+    pub alloc: Option<AllocStats>,
+}
+
+impl<T> Timed<T> {
+    /// Run and time a step.
+    pub fn measure(f: impl FnOnce() -> Result<T, Error>) -> Self {
+        let start = Instant::now();
+        let (result, alloc) = alloc::measure(f);
+        let time = start.elapsed();
+
+        Self {
+            result,
+            time: Some(time),
+            alloc,
+        }
+    }
+
+    /// Run a step `repetitions` times and keep the fastest run, or the first failure.
+    pub fn fastest(repetitions: usize, run: impl Fn() -> Self) -> Self {
+        let mut fastest = run();
+        for _ in 1..repetitions {
+            if fastest.result.is_err() {
+                break;
+            }
+            let timed = run();
+            if timed.result.is_err() || timed.time < fastest.time {
+                fastest = timed;
+            }
+        }
+        fastest
+    }
+
+    /// A step that was not run, because of an error.
+    pub fn skipped(error: Error) -> Self {
+        Self {
+            result: Err(error),
+            time: None,
+            alloc: None,
+        }
+    }
+}
+
+/// A table summarizing a list of reports.
+pub struct Summary<'a>(pub &'a [PartReport]);
+
+impl fmt::Display for Summary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = ["Day", "Part", "Answer", "Parse", "Solve", "Status"];
+        let show_parse: Vec<bool> = (0..self.0.len())
+            .map(|idx| shows_parse(self.0, idx))
+            .collect();
+        let rows: Vec<[String; 6]> = self
+            .0
+            .iter()
+            .zip(&show_parse)
+            .map(|(report, &show)| summary_row(report, show))
+            .collect();
+
+        // The allocations are only shown when they are counted
+        if self.0.iter().any(|r| r.parse_alloc.is_some()) {
+            let format_alloc = |a: Option<AllocStats>| a.map_or("-".to_string(), |a| a.to_string());
+            let header = [
+                header[0],
+                header[1],
+                header[2],
+                header[3],
+                "Parse allocs",
+                header[4],
+                "Solve allocs",
+                header[5],
+            ];
+            let rows: Vec<[String; 8]> = rows
+                .into_iter()
+                .zip(self.0.iter().zip(&show_parse))
+                .map(
+                    |([day, part, answer, parse, solve, status], (report, &show))| {
+                        [
+                            day,
+                            part,
+                            answer,
+                            parse,
+                            if show {
+                                format_alloc(report.parse_alloc)
+                            } else {
+                                String::new()
+                            },
+                            solve,
+                            format_alloc(report.solve_alloc),
+                            status,
+                        ]
+                    },
+                )
+                .collect();
+            table::write_table(f, header, &rows)?;
+        } else {
+            table::write_table(f, header, &rows)?;
+        }
+
+        let jobs = self.0.iter().map(|r| r.jobs).max().unwrap_or(1);
+        if jobs > 1 {
+            writeln!(
+                f,
+                "The parts ran on {} threads, their timings may include contention",
+                jobs
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Should the parsing of a report be shown in a summary?
+///
+/// When the input of a day is parsed once, its parsing is only shown on the first row of the
+/// day.
+pub(crate) fn shows_parse(reports: &[PartReport], idx: usize) -> bool {
+    let report = &reports[idx];
+    !report.shared_parse || idx == 0 || reports[idx - 1].day != report.day
+}
+
+/// The cells of a report in a [`Summary`], the parsing only shown if `show_parse`, see
+/// [`shows_parse`].
+pub(crate) fn summary_row(report: &PartReport, show_parse: bool) -> [String; 6] {
+    let format_duration = |d: Option<Duration>| d.map_or("-".to_string(), |d| format!("{:?}", d));
+
+    let (answer, status) = match &report.answer {
+        Ok(answer) => (answer.summary(), "ok".to_string()),
+        Err(error) => ("-".to_string(), format!("{}: {}", error.status(), error)),
+    };
+
+    [
+        report.day.to_string(),
+        part_label(report.part, &report.variant),
+        answer,
+        if show_parse {
+            format_duration(report.parse_time)
+        } else {
+            String::new()
+        },
+        format_duration(report.solve_time),
+        status,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemory;
+
+    #[test]
+    fn test_runner_continues_after_failure() {
+        let reports = Runner::new(2020)
+            .days(&[1, 99])
+            .input(InMemory::new().with(2020, 1, "1010\n1010\n"))
+            .run();
+
+        assert_eq!(reports.len(), 4);
+        assert_eq!(
+            reports[0].answer.as_ref().unwrap(),
+            &Answer::Integer(1020100)
+        );
+        assert!(reports[0].parse_time.is_some());
+        assert!(reports[1].answer.is_err());
+        assert!(reports[1].solve_time.is_some());
+        assert!(matches!(reports[2].answer, Err(Error::DayOutOfRange(99))));
+        assert!(reports[3].parse_time.is_none());
+    }
+
+    #[test]
+    fn test_variants() {
+        let input = InMemory::new().with(2020, 10, "16\n10\n15\n5\n1\n11\n7\n19\n6\n12\n4");
+
+        let runner = Runner::new(2020).days(&[10]).input(input);
+
+        let reports = runner.clone().variants(Variants::All).run();
+        let labels: Vec<_> = reports
+            .iter()
+            .map(|r| part_label(r.part, &r.variant))
+            .collect();
+        assert_eq!(labels, vec!["1", "2", "2 (telly)"]);
+        assert!(reports.iter().all(PartReport::is_ok));
+        assert!(disagreements(&reports).is_empty());
+
+        let reports = runner.clone().variants(Variants::Named("telly")).run();
+        assert_eq!(reports[0].variant, DEFAULT_VARIANT);
+        assert_eq!(reports[1].variant, "telly");
+
+        let reports = runner.variants(Variants::Named("nope")).run();
+        assert!(matches!(
+            reports[0].answer,
+            Err(Error::UnknownVariant { part: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_named_variant_on_several_days() {
+        let input = InMemory::new()
+            .with(2020, 1, "1721\n979\n366\n299\n675\n1456\n")
+            .with(2020, 10, "16\n10\n15\n5\n1\n11\n7\n19\n6\n12\n4");
+        let runner = Runner::new(2020).days(&[1, 10]).input(input);
+
+        // Only day 10 has the variant, the other days run their default implementations
+        let reports = runner.clone().variants(Variants::Named("telly")).run();
+        let labels: Vec<_> = reports
+            .iter()
+            .map(|r| (r.day, part_label(r.part, &r.variant)))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                (1, "1".to_string()),
+                (1, "2".to_string()),
+                (10, "1".to_string()),
+                (10, "2 (telly)".to_string())
+            ]
+        );
+        assert!(reports.iter().all(PartReport::is_ok));
+        assert!(!runner
+            .clone()
+            .parts(&[1])
+            .variants(Variants::Named("telly"))
+            .variant_known());
+
+        // No selected day has it
+        let reports = runner.variants(Variants::Named("nope")).run();
+        assert_eq!(reports.len(), 4);
+        assert!(reports
+            .iter()
+            .all(|r| matches!(r.answer, Err(Error::UnknownVariant { .. }))));
+    }
+
+    #[test]
+    fn test_runner_in_parallel() {
+        let days = [1, 2, 3, 4, 5, 6, 99];
+        let runner = Runner::new(2020).days(&days);
+        let sequential = runner.run();
+        let parallel = runner.jobs(4).run();
+
+        assert_eq!(parallel.len(), sequential.len());
+        for (s, p) in sequential.iter().zip(&parallel) {
+            assert_eq!((s.day, s.part), (p.day, p.part));
+            assert_eq!(s.answer.as_ref().ok(), p.answer.as_ref().ok());
+            assert_eq!(p.jobs, 4);
+        }
+        assert!(parallel.last().unwrap().answer.is_err());
+    }
+
+    #[test]
+    fn test_runner_options() {
+        let runner = Runner::new(2020)
+            .days(&[1, 2])
+            .parts(&[2])
+            .input(
+                InMemory::new()
+                    .with(2020, 1, "1010\n1010\n")
+                    .with(2020, 2, "1010\n1010\n"),
+            )
+            .repetitions(3);
+
+        let mut sunk = Vec::new();
+        let reports = runner.run_with(|report| sunk.push((report.day, report.part)));
+        assert_eq!(sunk, vec![(1, 2), (2, 2)]);
+        assert_eq!(
+            reports.iter().map(|r| (r.day, r.part)).collect::<Vec<_>>(),
+            sunk
+        );
+        assert!(reports[0].answer.is_err());
+        assert!(reports[0].solve_time.is_some());
+        assert!(matches!(reports[1].answer, Err(Error::Parse { .. })));
+
+        let reports = Runner::new(2020).days(&[1]).parts(&[3]).run();
+        assert!(matches!(reports[0].answer, Err(Error::InvalidPart(3))));
+    }
+
+    #[test]
+    fn test_fastest() {
+        let runs = std::cell::Cell::new(0);
+        let fastest = Timed::fastest(3, || {
+            runs.set(runs.get() + 1);
+            Timed {
+                result: Ok(runs.get()),
+                time: Some(Duration::from_millis(10 - runs.get())),
+                alloc: None,
+            }
+        });
+        assert_eq!(fastest.result.unwrap(), 3);
+
+        runs.set(0);
+        let failed = Timed::<()>::fastest(3, || {
+            runs.set(runs.get() + 1);
+            Timed::skipped(Error::InvalidPart(3))
+        });
+        assert!(failed.result.is_err());
+        assert_eq!(runs.get(), 1);
+    }
+
+    #[test]
+    fn test_map_parallel_keeps_order() {
+        let items: Vec<u64> = (0..100).collect();
+        assert_eq!(
+            map_parallel(&items, 8, |x| x * 2),
+            items.iter().map(|x| x * 2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_disagreements() {
+        let report = |part, variant: &str, answer: &str| PartReport {
+            day: 10,
+            part,
+            variant: variant.to_string(),
+            input: String::new(),
+            answer: Ok(Answer::from(answer)),
+            parse_time: None,
+            solve_time: None,
+            parse_alloc: None,
+            solve_alloc: None,
+            shared_parse: true,
+            jobs: 1,
+        };
+
+        let reports = [
+            report(1, DEFAULT_VARIANT, "35"),
+            report(2, DEFAULT_VARIANT, "8"),
+            report(2, "telly", "8"),
+            report(2, "other", "9"),
+        ];
+        assert_eq!(disagreements(&reports), vec![(10, 2)]);
+    }
+
+    #[test]
+    fn test_summary() {
+        let reports = [
+            PartReport {
+                day: 1,
+                part: 1,
+                variant: DEFAULT_VARIANT.to_string(),
+                input: "inputs/01.txt".to_string(),
+                answer: Ok(Answer::Integer(42)),
+                parse_time: Some(Duration::from_millis(1)),
+                solve_time: Some(Duration::from_millis(2)),
+                parse_alloc: None,
+                solve_alloc: None,
+                shared_parse: true,
+                jobs: 1,
+            },
+            PartReport {
+                day: 1,
+                part: 2,
+                variant: DEFAULT_VARIANT.to_string(),
+                input: "inputs/01.txt".to_string(),
+                answer: Err(Error::solve(
+                    1,
+                    2,
+                    anyhow::anyhow!("Could not find combination"),
+                )),
+                parse_time: Some(Duration::from_millis(1)),
+                solve_time: Some(Duration::from_millis(2)),
+                parse_alloc: None,
+                solve_alloc: None,
+                shared_parse: true,
+                jobs: 1,
+            },
+        ];
+
+        assert_eq!(
+            Summary(&reports).to_string(),
+            "\
+Day | Part | Answer | Parse | Solve | Status
+1   | 1    | 42     | 1ms   | 2ms   | ok
+1   | 2    | -      |       | 2ms   | error: Could not find combination
+"
+        );
+
+        // Each isolated part parses the input again
+        let mut reports = reports;
+        reports[1].shared_parse = false;
+        assert!(Summary(&reports)
+            .to_string()
+            .contains("| 1ms   | 2ms   | error"));
+    }
+}
@@ -0,0 +1,215 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context as _, Result};
+
+use crate::registry::DAYS;
+use crate::Error;
+
+/// The module copied for each new day, with `XX` standing for the day number.
+const TEMPLATE: &str = include_str!("dayXX.rs");
+
+/// Generate the module of a day of 2020 from the template and register it, in the repository at
+/// `root`.
+///
+/// Also create empty `inputs/NN.txt` and `inputs/NN.example.txt` files, unless they exist.
+/// Fails without writing anything if the day already has a module or is already registered.
+///
+/// Return the created files, and `src/lib.rs`.
+pub fn new_day(root: &Path, day: u32) -> Result<Vec<PathBuf>> {
+    if !DAYS.contains(&day) {
+        return Err(Error::DayOutOfRange(day).into());
+    }
+
+    let lib_path = root.join("src").join("lib.rs");
+    let module_path = root.join("src").join(format!("day{:02}.rs", day));
+    let input_paths = [
+        root.join("inputs").join(format!("{:02}.txt", day)),
+        root.join("inputs").join(format!("{:02}.example.txt", day)),
+    ];
+
+    let lib = fs::read_to_string(&lib_path).with_context(|| {
+        format!(
+            "while reading {}, is {} the root of the repository?",
+            lib_path.display(),
+            root.display()
+        )
+    })?;
+    if module_path.exists() {
+        bail!("{} already exists", module_path.display());
+    }
+    let lib = register(&lib, day)?;
+
+    create_new(&module_path, &render_template(day))?;
+    fs::write(&lib_path, lib).with_context(|| format!("while writing {}", lib_path.display()))?;
+
+    let mut created = vec![module_path, lib_path];
+    fs::create_dir_all(root.join("inputs"))?;
+    for path in &input_paths {
+        if !path.exists() {
+            create_new(path, "")?;
+            created.push(path.clone());
+        }
+    }
+
+    Ok(created)
+}
+
+fn create_new(path: &Path, content: &str) -> Result<()> {
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .with_context(|| format!("while creating {}", path.display()))
+}
+
+/// The template with the day number filled in.
+fn render_template(day: u32) -> String {
+    TEMPLATE
+        .replace(
+            "DAY_NUMBER: u32 = XX",
+            &format!("DAY_NUMBER: u32 = {}", day),
+        )
+        .replace("XX", &format!("{:02}", day))
+}
+
+/// Add the `mod` declaration and the `CHALLENGES` entry of a day to the source of `lib.rs`.
+fn register(lib: &str, day: u32) -> Result<String> {
+    let module = format!("mod day{:02};", day);
+    let entry = format!(
+        "    ({}, &ChallengeImpl(day{:02}::Day{:02})),",
+        day, day, day
+    );
+
+    let mut lines: Vec<&str> = lib.lines().collect();
+    if lines.contains(&module.as_str()) {
+        bail!("Day {} is already registered", day);
+    }
+
+    let module_idx = insertion_index(&lines, 0, day, |line| {
+        line.strip_prefix("mod day")?
+            .strip_suffix(';')?
+            .parse()
+            .ok()
+    })
+    .ok_or_else(|| anyhow!("Cannot find the day modules in lib.rs"))?;
+    lines.insert(module_idx, &module);
+
+    let challenges_idx = lines
+        .iter()
+        .position(|line| line.starts_with("static CHALLENGES"))
+        .ok_or_else(|| anyhow!("Cannot find CHALLENGES in lib.rs"))?;
+    let entry_idx = insertion_index(&lines, challenges_idx, day, |line| {
+        line.trim_start()
+            .strip_prefix('(')?
+            .split(',')
+            .next()?
+            .parse()
+            .ok()
+    })
+    .ok_or_else(|| anyhow!("Cannot find the entries of CHALLENGES in lib.rs"))?;
+    lines.insert(entry_idx, &entry);
+
+    let mut lib = lines.join("\n");
+    lib.push('\n');
+    Ok(lib)
+}
+
+/// Where to insert `day` among the first consecutive lines after `start` that `day_of` parses.
+///
+/// The days are kept in order.
+fn insertion_index(
+    lines: &[&str],
+    start: usize,
+    day: u32,
+    day_of: impl Fn(&str) -> Option<u32>,
+) -> Option<usize> {
+    let first = start
+        + lines[start..]
+            .iter()
+            .position(|line| day_of(line).is_some())?;
+    let mut idx = first;
+    while let Some(other) = lines.get(idx).and_then(|line| day_of(line)) {
+        if other > day {
+            break;
+        }
+        idx += 1;
+    }
+    Some(idx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "\
+mod answers;
+mod day01;
+mod day03;
+mod error;
+
+static CHALLENGES: &[(u32, &Solver)] = &[
+    (1, &ChallengeImpl(day01::Day01)),
+    (3, &ChallengeImpl(day03::Day03)),
+];
+";
+
+    #[test]
+    fn test_render_template() {
+        let module = render_template(7);
+
+        assert!(module.contains("pub struct Day07;"));
+        assert!(module.contains("const DAY_NUMBER: u32 = 7;"));
+        assert!(module.contains("crate::benchmark_challenge!(crate::day07::Day07);"));
+        assert!(!module.contains("XX"));
+    }
+
+    #[test]
+    fn test_register() {
+        assert_eq!(
+            register(LIB, 2).unwrap(),
+            "\
+mod answers;
+mod day01;
+mod day02;
+mod day03;
+mod error;
+
+static CHALLENGES: &[(u32, &Solver)] = &[
+    (1, &ChallengeImpl(day01::Day01)),
+    (2, &ChallengeImpl(day02::Day02)),
+    (3, &ChallengeImpl(day03::Day03)),
+];
+"
+        );
+
+        let registered = register(LIB, 13).unwrap();
+        assert!(registered.contains("mod day03;\nmod day13;\nmod error;"));
+        assert!(registered.contains(
+            "    (3, &ChallengeImpl(day03::Day03)),\n    (13, &ChallengeImpl(day13::Day13)),\n];"
+        ));
+    }
+
+    #[test]
+    fn test_refuse_existing_day() {
+        assert!(register(LIB, 3).is_err());
+
+        let root = std::env::temp_dir().join(format!("advent2020-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src").join("lib.rs"), LIB).unwrap();
+
+        let created = new_day(&root, 4).unwrap();
+        assert_eq!(created.len(), 4);
+        assert!(root.join("inputs").join("04.example.txt").exists());
+        assert!(new_day(&root, 4).is_err());
+        let error = new_day(&root, 26).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::DayOutOfRange(26))
+        ));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,77 @@
+use anyhow::{anyhow, Context as _, Result};
+
+use crate::registry::DAYS;
+use crate::Error;
+
+/// Parse a day selection such as `1-5,9` into a sorted list of days, without duplicates.
+///
+/// Every day must be in [`DAYS`](crate::DAYS), see [`Error::DayOutOfRange`].
+pub fn parse_days(selection: &str) -> Result<Vec<u32>> {
+    let mut days = Vec::new();
+
+    for range in selection.split(',').map(str::trim) {
+        let (first, last) = match range.find('-') {
+            Some(idx) => (parse_day(&range[..idx])?, parse_day(&range[idx + 1..])?),
+            None => {
+                let day = parse_day(range)?;
+                (day, day)
+            }
+        };
+
+        if first > last {
+            return Err(anyhow!("Invalid day range {}", range));
+        }
+
+        days.extend(first..=last);
+    }
+
+    days.sort_unstable();
+    days.dedup();
+
+    Ok(days)
+}
+
+fn parse_day(s: &str) -> Result<u32> {
+    let day = s
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid day {:?}", s))?;
+    if !DAYS.contains(&day) {
+        return Err(Error::DayOutOfRange(day).into());
+    }
+
+    Ok(day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days("3").unwrap(), vec![3]);
+        assert_eq!(parse_days("1-5,9").unwrap(), vec![1, 2, 3, 4, 5, 9]);
+        assert_eq!(parse_days("9, 2-3,3").unwrap(), vec![2, 3, 9]);
+    }
+
+    #[test]
+    fn test_parse_days_invalid() {
+        assert!(parse_days("").is_err());
+        assert!(parse_days("5-1").is_err());
+        assert!(parse_days("1-a").is_err());
+    }
+
+    #[test]
+    fn test_parse_days_out_of_range() {
+        let out_of_range = |selection, expected| match parse_days(selection) {
+            Err(error) => matches!(
+                error.downcast_ref::<Error>(),
+                Some(&Error::DayOutOfRange(day)) if day == expected
+            ),
+            Ok(_) => false,
+        };
+        assert!(out_of_range("26", 26));
+        assert!(out_of_range("0,3", 0));
+        assert!(out_of_range("1-4000000000", 4000000000));
+    }
+}
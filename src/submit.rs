@@ -0,0 +1,556 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::fetch::{DEFAULT_BASE_URL, USER_AGENT};
+use crate::registry::DAYS;
+use crate::{Answer, AnswerStore, Error};
+
+/// The default location of the submission log, relative to the working directory.
+pub const DEFAULT_SUBMISSIONS_PATH: &str = "submissions.jsonl";
+
+/// What the site answered to a submission.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without telling whether too high or too low.
+    Wrong,
+    /// An answer was submitted too recently, the answer was not checked.
+    Wait,
+    /// The part is already solved, the answer was not checked.
+    AlreadySolved,
+}
+
+impl Verdict {
+    pub fn is_wrong(self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "too high"),
+            Verdict::TooLow => write!(f, "too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::Wait => write!(f, "submitted too recently"),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+/// The parsed response to a submission.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Response {
+    pub verdict: Verdict,
+    /// How long to wait before submitting again, if the site says so.
+    pub cooldown: Option<Duration>,
+}
+
+impl Response {
+    /// Parse the HTML page answering a submission.
+    pub fn parse(html: &str) -> Result<Self> {
+        let verdict = if html.contains("That's the right answer") {
+            Verdict::Correct
+        } else if html.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if html.contains("your answer is too low") {
+            Verdict::TooLow
+        } else if html.contains("That's not the right answer") {
+            Verdict::Wrong
+        } else if html.contains("You gave an answer too recently") {
+            Verdict::Wait
+        } else if html.contains("Did you already complete it") {
+            Verdict::AlreadySolved
+        } else {
+            bail!("Unexpected response: {}", article(html));
+        };
+
+        Ok(Self {
+            verdict,
+            cooldown: parse_cooldown(html),
+        })
+    }
+}
+
+/// The text of the `<article>` of a page, where the site writes its message.
+fn article(html: &str) -> &str {
+    let start = html
+        .find("<article>")
+        .map_or(0, |idx| idx + "<article>".len());
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |idx| start + idx);
+    html[start..end].trim()
+}
+
+/// Parse "You have 1m 34s left to wait" or "Please wait 5 minutes before trying again".
+fn parse_cooldown(html: &str) -> Option<Duration> {
+    if let Some(idx) = html.find("You have ") {
+        let rest = &html[idx + "You have ".len()..];
+        let left = &rest[..rest.find(" left to wait")?];
+        return left
+            .split_whitespace()
+            .try_fold(Duration::ZERO, |total, token| {
+                let (number, unit) = token.split_at(token.find(|c: char| !c.is_ascii_digit())?);
+                let number: u64 = number.parse().ok()?;
+                let seconds = match unit {
+                    "h" => number * 3600,
+                    "m" => number * 60,
+                    "s" => number,
+                    _ => return None,
+                };
+                Some(total + Duration::from_secs(seconds))
+            });
+    }
+
+    // Either "Please" or "please"
+    let idx = html.find("lease wait ")?;
+    let mut words = html[idx + "lease wait ".len()..].split_whitespace();
+    let number = match words.next()? {
+        "one" | "a" => 1,
+        number => number.parse().ok()?,
+    };
+    match words.next()?.trim_end_matches(|c: char| !c.is_alphabetic()) {
+        "second" | "seconds" => Some(Duration::from_secs(number)),
+        "minute" | "minutes" => Some(Duration::from_secs(number * 60)),
+        _ => None,
+    }
+}
+
+/// A submission of the log.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Submission {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    pub answer: Answer,
+    pub verdict: Verdict,
+    /// When the answer was submitted, in RFC 3339.
+    pub timestamp: String,
+    /// When answers can be submitted again, in RFC 3339.
+    pub retry_at: Option<String>,
+}
+
+/// Why an answer is not submitted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Refusal {
+    /// The part was already solved with this answer.
+    Solved(Answer),
+    /// The answer was already submitted and is wrong.
+    KnownWrong(Verdict),
+    /// The answer is at least as high as one known to be too high.
+    AboveTooHigh(Answer),
+    /// The answer is at most as low as one known to be too low.
+    BelowTooLow(Answer),
+    /// The site asked to wait before submitting again.
+    Cooldown(Duration),
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::Solved(answer) => write!(f, "the part is already solved, with {}", answer),
+            Refusal::KnownWrong(verdict) => {
+                write!(f, "this answer was already submitted, and is {}", verdict)
+            }
+            Refusal::AboveTooHigh(answer) => write!(f, "{} is already too high", answer),
+            Refusal::BelowTooLow(answer) => write!(f, "{} is already too low", answer),
+            Refusal::Cooldown(wait) => {
+                write!(
+                    f,
+                    "the site asks to wait {}s before submitting",
+                    wait.as_secs()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Refusal {}
+
+/// The submissions made so far, stored as JSON lines.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SubmissionLog {
+    submissions: Vec<Submission>,
+}
+
+impl SubmissionLog {
+    /// Load the log from a file. A missing file is an empty log.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(error) => {
+                return Err(error).with_context(|| format!("while reading {}", path.display()))
+            }
+        };
+
+        let submissions = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                serde_json::from_str(line).with_context(|| {
+                    format!("while parsing {}, on line {}", path.display(), idx + 1)
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { submissions })
+    }
+
+    /// Append a submission to the log file, creating it if needed.
+    pub fn append(path: &Path, submission: &Submission) -> Result<()> {
+        let mut line = serde_json::to_string(submission)?;
+        line.push('\n');
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .with_context(|| format!("while writing {}", path.display()))
+    }
+
+    pub fn submissions(&self) -> &[Submission] {
+        &self.submissions
+    }
+
+    /// Check that submitting `answer` at `now` could be useful and is allowed.
+    pub fn check(
+        &self,
+        year: u32,
+        day: u32,
+        part: u8,
+        answer: &Answer,
+        now: DateTime<Utc>,
+    ) -> Result<(), Refusal> {
+        let submissions = self
+            .submissions
+            .iter()
+            .filter(|s| (s.year, s.day, s.part) == (year, day, part));
+
+        for submission in submissions {
+            let same = submission.answer.matches(answer);
+            let cmp = answer.numeric_cmp(&submission.answer);
+            match submission.verdict {
+                Verdict::Correct => return Err(Refusal::Solved(submission.answer.clone())),
+                verdict if verdict.is_wrong() && same => return Err(Refusal::KnownWrong(verdict)),
+                Verdict::TooHigh if cmp == Some(Ordering::Greater) => {
+                    return Err(Refusal::AboveTooHigh(submission.answer.clone()))
+                }
+                Verdict::TooLow if cmp == Some(Ordering::Less) => {
+                    return Err(Refusal::BelowTooLow(submission.answer.clone()))
+                }
+                _ => {}
+            }
+        }
+
+        // The cooldowns are per account, whatever the part
+        let retry_at = self
+            .submissions
+            .iter()
+            .filter_map(|s| DateTime::parse_from_rfc3339(s.retry_at.as_deref()?).ok())
+            .max();
+        match retry_at.map(|retry_at| retry_at.with_timezone(&Utc) - now) {
+            Some(wait) if wait > chrono::Duration::zero() => {
+                Err(Refusal::Cooldown(wait.to_std().unwrap_or_default()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Posts answers, and keeps track of them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Submitter {
+    pub base_url: String,
+    /// The value of the `session` cookie of a logged-in user.
+    pub session: String,
+    /// The year of the event of the answers.
+    pub year: u32,
+}
+
+impl Submitter {
+    pub fn new(session: String, year: u32) -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session,
+            year,
+        }
+    }
+
+    /// Submit an answer, unless the log tells it is pointless or not allowed yet.
+    ///
+    /// The submission is appended to the log at `log_path`, and a correct answer is saved in
+    /// the answer store at `answers_path`.
+    pub fn submit(
+        &self,
+        log_path: &Path,
+        answers_path: &Path,
+        day: u32,
+        part: u8,
+        answer: &Answer,
+    ) -> Result<Response> {
+        check_part(day, part)?;
+        let now = Utc::now();
+        SubmissionLog::load(log_path)?.check(self.year, day, part, answer, now)?;
+
+        let response = self.post(day, part, answer)?;
+
+        let retry_at = response.cooldown.map(|cooldown| {
+            let retry_at = now
+                + chrono::Duration::from_std(cooldown).unwrap_or_else(|_| chrono::Duration::zero());
+            retry_at.to_rfc3339_opts(SecondsFormat::Secs, true)
+        });
+        let submission = Submission {
+            year: self.year,
+            day,
+            part,
+            answer: answer.clone(),
+            verdict: response.verdict,
+            timestamp: now.to_rfc3339_opts(SecondsFormat::Secs, true),
+            retry_at,
+        };
+        SubmissionLog::append(log_path, &submission)?;
+
+        if response.verdict == Verdict::Correct {
+            let mut store = AnswerStore::load(answers_path)?;
+            store.set(day, part, answer.clone())?;
+            store.save(answers_path)?;
+        }
+
+        Ok(response)
+    }
+
+    /// Post an answer without checking the log, only that the day and part exist.
+    pub fn post(&self, day: u32, part: u8, answer: &Answer) -> Result<Response> {
+        check_part(day, part)?;
+        let url = format!(
+            "{}/{}/day/{}/answer",
+            self.base_url.trim_end_matches('/'),
+            self.year,
+            day
+        );
+        let response = ureq::post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .send_form(&[
+                ("level", &part.to_string()),
+                ("answer", &answer.to_string()),
+            ]);
+
+        let html = match response {
+            Ok(response) => response
+                .into_string()
+                .with_context(|| format!("while reading the response of {}", url))?,
+            Err(ureq::Error::Status(code, _)) => {
+                return Err(anyhow!(
+                    "{} answered {}, is the session token valid?",
+                    url,
+                    code
+                ))
+            }
+            Err(error) => return Err(error).with_context(|| format!("while posting to {}", url)),
+        };
+
+        Response::parse(&html)
+    }
+}
+
+/// Refuse the days and parts the site does not have, before any request.
+fn check_part(day: u32, part: u8) -> Result<(), Error> {
+    if !DAYS.contains(&day) {
+        return Err(Error::DayOutOfRange(day));
+    }
+    if !(1..=2).contains(&part) {
+        return Err(Error::InvalidPart(part));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    fn page(message: &str) -> String {
+        format!(
+            "<html><main>\n<article><p>{}</p></article>\n</main></html>",
+            message
+        )
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let parse = |message: &str| Response::parse(&page(message)).unwrap();
+
+        assert_eq!(
+            parse("That's the right answer!  You are one gold star closer."),
+            Response {
+                verdict: Verdict::Correct,
+                cooldown: None
+            }
+        );
+        assert_eq!(
+            parse("That's not the right answer; your answer is too high.  Please wait one minute before trying again."),
+            Response {
+                verdict: Verdict::TooHigh,
+                cooldown: Some(Duration::from_secs(60))
+            }
+        );
+        assert_eq!(
+            parse("That's not the right answer; your answer is too low.  please wait 5 minutes before trying again.").cooldown,
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(
+            parse("That's not the right answer.").verdict,
+            Verdict::Wrong
+        );
+        assert_eq!(
+            parse("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 34s left to wait."),
+            Response {
+                verdict: Verdict::Wait,
+                cooldown: Some(Duration::from_secs(94))
+            }
+        );
+        assert_eq!(
+            parse("You don't seem to be solving the right level.  Did you already complete it?")
+                .verdict,
+            Verdict::AlreadySolved
+        );
+        assert!(Response::parse(&page("Something else")).is_err());
+    }
+
+    fn submission(part: u8, answer: i64, verdict: Verdict, retry_at: Option<&str>) -> Submission {
+        Submission {
+            year: 2020,
+            day: 1,
+            part,
+            answer: Answer::Integer(answer),
+            verdict,
+            timestamp: "2020-12-01T06:00:00Z".to_string(),
+            retry_at: retry_at.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let log = SubmissionLog {
+            submissions: vec![
+                submission(1, 100, Verdict::TooHigh, None),
+                submission(1, 10, Verdict::TooLow, None),
+                submission(1, 50, Verdict::Wrong, Some("2020-12-01T06:01:00Z")),
+                submission(2, 7, Verdict::Correct, None),
+            ],
+        };
+        let now = DateTime::parse_from_rfc3339("2020-12-01T06:00:30Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let later = now + chrono::Duration::minutes(1);
+        let check =
+            |part, answer: i64, now| log.check(2020, 1, part, &Answer::Integer(answer), now);
+
+        assert_eq!(
+            check(1, 50, later),
+            Err(Refusal::KnownWrong(Verdict::Wrong))
+        );
+        assert_eq!(
+            check(1, 150, later),
+            Err(Refusal::AboveTooHigh(Answer::Integer(100)))
+        );
+        assert_eq!(
+            check(1, 5, later),
+            Err(Refusal::BelowTooLow(Answer::Integer(10)))
+        );
+        assert_eq!(
+            check(1, 60, now),
+            Err(Refusal::Cooldown(Duration::from_secs(30)))
+        );
+        assert_eq!(check(1, 60, later), Ok(()));
+        assert_eq!(check(2, 8, later), Err(Refusal::Solved(Answer::Integer(7))));
+    }
+
+    #[test]
+    fn test_submit() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let submitter = Submitter {
+            base_url: format!("http://{}", listener.local_addr().unwrap()),
+            session: "s3cr3t".to_string(),
+            year: 2020,
+        };
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = String::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with("answer=42") {
+                let n = stream.read(&mut buffer).unwrap();
+                request.push_str(std::str::from_utf8(&buffer[..n]).unwrap());
+            }
+            let body = page("That's the right answer!");
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            request
+        });
+
+        let dir = std::env::temp_dir().join(format!("advent2020-submit-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let (log_path, answers_path) = (dir.join("submissions.jsonl"), dir.join("answers.toml"));
+
+        let answer = Answer::Integer(42);
+        let response = submitter
+            .submit(&log_path, &answers_path, 3, 2, &answer)
+            .unwrap();
+        assert_eq!(response.verdict, Verdict::Correct);
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /2020/day/3/answer HTTP/1.1\r\n"));
+        assert!(request.contains("session=s3cr3t"));
+        assert!(request.ends_with("level=2&answer=42"));
+
+        assert_eq!(
+            AnswerStore::load(&answers_path).unwrap().get(3, 2),
+            Some(&answer)
+        );
+        // Refused without contacting the server, which is gone
+        let error = submitter
+            .submit(&log_path, &answers_path, 3, 2, &answer)
+            .unwrap_err();
+        assert!(error.is::<Refusal>());
+        let error = submitter
+            .submit(&log_path, &answers_path, 99, 1, &answer)
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::DayOutOfRange(99))
+        ));
+        let error = submitter.post(1, 3, &answer).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::InvalidPart(3))
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
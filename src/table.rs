@@ -0,0 +1,28 @@
+use std::fmt;
+
+/// Write rows as a table with aligned columns, separated by pipes.
+pub fn write_table<const N: usize>(
+    f: &mut fmt::Formatter<'_>,
+    header: [&str; N],
+    rows: &[[String; N]],
+) -> fmt::Result {
+    let mut widths = header.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let header = header.map(str::to_string);
+    for row in std::iter::once(&header).chain(rows) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        writeln!(f, "{}", line.trim_end())?;
+    }
+
+    Ok(())
+}
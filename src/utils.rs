@@ -1,5 +1,268 @@
+use std::cell::Cell;
+use std::fmt;
 use std::str::FromStr;
 
-pub fn parse_line_separated_list<T: FromStr>(content: &str) -> Result<Vec<T>, T::Err> {
-    content.lines().map(str::parse).collect()
+use crate::Location;
+
+thread_local! {
+    static COLLECT_ALL_ERRORS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `f`, making the parsers report every invalid line instead of stopping at the first one.
+pub fn with_all_parse_errors<T>(f: impl FnOnce() -> T) -> T {
+    let previous = COLLECT_ALL_ERRORS.with(|c| c.replace(true));
+    let result = f();
+    COLLECT_ALL_ERRORS.with(|c| c.set(previous));
+    result
+}
+
+/// Are the parsers of this thread reporting every invalid line?
+pub(crate) fn collecting_all_errors() -> bool {
+    COLLECT_ALL_ERRORS.with(Cell::get)
+}
+
+/// Parse each line with `FromStr`, and locate the lines that fail.
+pub fn parse_line_separated_list<T>(content: &str) -> anyhow::Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Into<anyhow::Error>,
+{
+    collect_parsed(content.lines().map(|line| {
+        line.parse()
+            .map_err(|e: T::Err| ParseError::on_line(content, line, e.into()))
+    }))
+}
+
+/// Collect parsed values, stopping at the first error unless all the errors are collected
+/// (see [`with_all_parse_errors`]).
+pub fn collect_parsed<T>(
+    results: impl IntoIterator<Item = Result<T, ParseError>>,
+) -> anyhow::Result<Vec<T>> {
+    let collect_all = collecting_all_errors();
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+
+    for result in results {
+        match result {
+            Ok(value) => values.push(value),
+            Err(error) => {
+                errors.push(error);
+                if !collect_all {
+                    break;
+                }
+            }
+        }
+    }
+
+    match errors.len() {
+        0 => Ok(values),
+        1 => Err(errors.pop().unwrap().into()),
+        _ => Err(ParseErrors(errors).into()),
+    }
+}
+
+/// An error on a token of a string, such as an invalid character of a line.
+///
+/// Parsers of a single line return it so the column of the token ends up in the diagnostic.
+#[derive(Debug)]
+pub struct TokenError {
+    offset: usize,
+    len: usize,
+    source: anyhow::Error,
+}
+
+impl TokenError {
+    /// `token` must be a slice of `s`.
+    pub fn new(s: &str, token: &str, source: impl Into<anyhow::Error>) -> Self {
+        Self {
+            offset: offset_in(s, token),
+            len: token.len(),
+            source: source.into(),
+        }
+    }
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#}", self.source)
+    }
+}
+
+impl std::error::Error for TokenError {}
+
+/// An error on a token of the input, with its location and the text of its line.
+#[derive(Debug)]
+pub struct ParseError {
+    pub location: Location,
+    /// The line containing the token.
+    pub text: String,
+    /// The width of the token, in characters.
+    pub width: usize,
+    pub source: anyhow::Error,
+}
+
+impl ParseError {
+    /// Locate an error on `token`, which must be a slice of `content`.
+    pub fn at(content: &str, token: &str, source: impl Into<anyhow::Error>) -> Self {
+        let offset = offset_in(content, token);
+        let line_start = content[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = content[offset..]
+            .find('\n')
+            .map_or(content.len(), |idx| offset + idx);
+
+        Self {
+            location: Location {
+                line: content[..offset].matches('\n').count() + 1,
+                column: Some(content[line_start..offset].chars().count() + 1),
+            },
+            text: content[line_start..line_end].trim_end().to_string(),
+            width: token.chars().count().max(1),
+            source: source.into(),
+        }
+    }
+
+    /// Locate an error on `line`, which must be a slice of `content`.
+    ///
+    /// If the error is a [`TokenError`], the error is located on its token.
+    pub fn on_line(content: &str, line: &str, error: anyhow::Error) -> Self {
+        match error.downcast::<TokenError>() {
+            Ok(token_error) => {
+                let token = &line[token_error.offset..token_error.offset + token_error.len];
+                Self::at(content, token, token_error.source)
+            }
+            Err(error) => Self::at(content, line.trim_end(), error),
+        }
+    }
+
+    /// Render the error like a compiler diagnostic. `origin` is the name of the input.
+    pub fn render(&self, origin: &str) -> String {
+        let line_number = self.location.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let column = self.location.column.unwrap_or(1);
+
+        format!(
+            "error: {:#}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}\n",
+            self.source,
+            gutter,
+            origin,
+            self.location.line,
+            column,
+            gutter,
+            line_number,
+            self.text,
+            gutter,
+            " ".repeat(column - 1),
+            "^".repeat(self.width),
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {:#}", self.location, self.source)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Several parse errors, when all the errors are collected.
+#[derive(Debug)]
+pub struct ParseErrors(pub Vec<ParseError>);
+
+impl ParseErrors {
+    pub fn render(&self, origin: &str) -> String {
+        self.0
+            .iter()
+            .map(|error| error.render(origin))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (and {} more errors)", self.0[0], self.0.len() - 1)
+    }
+}
+
+impl std::error::Error for ParseErrors {}
+
+/// The byte offset of `token` in `s`, of which it must be a slice.
+fn offset_in(s: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize).wrapping_sub(s.as_ptr() as usize);
+    assert!(
+        offset <= s.len() && offset + token.len() <= s.len(),
+        "The token must be a slice of the string"
+    );
+    offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_at() {
+        let content = "abc\nde fgh\n";
+        let error = ParseError::at(content, &content[7..10], anyhow::anyhow!("bad"));
+
+        assert_eq!(
+            error.location,
+            Location {
+                line: 2,
+                column: Some(4)
+            }
+        );
+        assert_eq!(error.text, "de fgh");
+        assert_eq!(error.width, 3);
+    }
+
+    #[test]
+    fn test_token_error_on_line() {
+        let content = "nop +0\nfoo +1";
+        let line = &content[7..];
+        let error = ParseError::on_line(
+            content,
+            line,
+            TokenError::new(line, &line[4..], anyhow::anyhow!("bad")).into(),
+        );
+
+        assert_eq!(error.location.column, Some(5));
+        assert_eq!(error.width, 2);
+        assert_eq!(error.to_string(), "line 2, column 5: bad");
+    }
+
+    #[test]
+    fn test_render() {
+        let error = parse_line_separated_list::<u32>("1\n2\n3a")
+            .unwrap_err()
+            .downcast::<ParseError>()
+            .unwrap();
+
+        assert_eq!(
+            error.render("inputs/01.txt"),
+            "\
+error: invalid digit found in string
+ --> inputs/01.txt:3:1
+  |
+3 | 3a
+  | ^^
+"
+        );
+    }
+
+    #[test]
+    fn test_collect_all_errors() {
+        let content = "1\nx\n3\ny";
+
+        let first = parse_line_separated_list::<u32>(content).unwrap_err();
+        assert!(first.is::<ParseError>());
+
+        let all = with_all_parse_errors(|| parse_line_separated_list::<u32>(content))
+            .unwrap_err()
+            .downcast::<ParseErrors>()
+            .unwrap();
+        let lines: Vec<_> = all.0.iter().map(|e| e.location.line).collect();
+        assert_eq!(lines, vec![2, 4]);
+    }
 }
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::runner::{shows_parse, summary_row};
+use crate::{table, Answer, PartReport};
+
+/// Polls files, to notice when they change.
+///
+/// A change is only reported once the files stay unchanged for `debounce`, so that a burst of
+/// edits, or an editor writing a file in several steps, triggers a single run.
+#[derive(Debug)]
+pub struct Watcher {
+    /// How often the files are polled.
+    pub interval: Duration,
+    pub debounce: Duration,
+    /// The files, with their stamp at the last poll: `None` if they could not be read.
+    files: Vec<(PathBuf, Option<Stamp>)>,
+    /// When the last change not reported yet was seen.
+    pending: Option<Instant>,
+}
+
+/// The modification time and size of a file, to tell its versions apart.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Stamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let stamp = stamp(&path);
+                (path, stamp)
+            })
+            .collect();
+
+        Self {
+            interval: Duration::from_millis(200),
+            debounce: Duration::from_millis(300),
+            files,
+            pending: None,
+        }
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|(path, _)| path.as_path())
+    }
+
+    /// Block until the files change, then stay unchanged for `debounce`.
+    pub fn wait(&mut self) {
+        loop {
+            thread::sleep(self.interval);
+            if self.poll(Instant::now()) {
+                return;
+            }
+        }
+    }
+
+    /// Look at the files at `now`, and tell whether a change is settled.
+    fn poll(&mut self, now: Instant) -> bool {
+        for (path, last) in &mut self.files {
+            let current = stamp(path);
+            if current != *last {
+                *last = current;
+                self.pending = Some(now);
+            }
+        }
+
+        match self.pending {
+            Some(since) if now.duration_since(since) >= self.debounce => {
+                self.pending = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(Stamp {
+        modified: metadata.modified().ok()?,
+        len: metadata.len(),
+    })
+}
+
+/// How the answer of a part compares with the one of the previous run.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnswerChange {
+    /// The part has no previous answer.
+    New,
+    Unchanged,
+    Changed {
+        previous: Answer,
+    },
+    /// The part failed.
+    Failed,
+}
+
+impl fmt::Display for AnswerChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswerChange::New => write!(f, "new"),
+            AnswerChange::Unchanged => write!(f, "unchanged"),
+            AnswerChange::Changed { previous } => write!(f, "changed, was {}", previous.summary()),
+            AnswerChange::Failed => write!(f, "-"),
+        }
+    }
+}
+
+/// The last answer of every part, to tell which answers changed between the runs.
+///
+/// A failed part keeps its last answer, so that fixing an input compares with the answer
+/// from before the breakage.
+#[derive(Debug, Default)]
+pub struct PreviousAnswers(HashMap<(u32, u8, String), Answer>);
+
+impl PreviousAnswers {
+    /// Compare the answers with the previous ones, then remember them for the next run.
+    pub fn update(&mut self, reports: &[PartReport]) -> Vec<AnswerChange> {
+        reports
+            .iter()
+            .map(|report| {
+                let answer = match &report.answer {
+                    Ok(answer) => answer,
+                    Err(_) => return AnswerChange::Failed,
+                };
+                let key = (report.day, report.part, report.variant.clone());
+                match self.0.insert(key, answer.clone()) {
+                    None => AnswerChange::New,
+                    Some(previous) if previous == *answer => AnswerChange::Unchanged,
+                    Some(previous) => AnswerChange::Changed { previous },
+                }
+            })
+            .collect()
+    }
+}
+
+/// A table summarizing a run of the watch mode, with how the answers changed.
+pub struct WatchSummary<'a>(pub &'a [PartReport], pub &'a [AnswerChange]);
+
+impl fmt::Display for WatchSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = [
+            "Day", "Part", "Answer", "Parse", "Solve", "Change", "Status",
+        ];
+        let rows: Vec<[String; 7]> = self
+            .0
+            .iter()
+            .zip(self.1)
+            .enumerate()
+            .map(|(idx, (report, change))| {
+                let [day, part, answer, parse, solve, status] =
+                    summary_row(report, shows_parse(self.0, idx));
+                [day, part, answer, parse, solve, change.to_string(), status]
+            })
+            .collect();
+
+        table::write_table(f, header, &rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::Error;
+
+    fn report(part: u8, answer: Result<i64, Error>) -> PartReport {
+        PartReport {
+            day: 1,
+            part,
+            variant: crate::DEFAULT_VARIANT.to_string(),
+            input: "inputs/01.txt".to_string(),
+            answer: answer.map(Answer::from),
+            parse_time: None,
+            solve_time: None,
+            parse_alloc: None,
+            solve_alloc: None,
+            shared_parse: true,
+            jobs: 1,
+        }
+    }
+
+    #[test]
+    fn test_debounce() {
+        let path = env::temp_dir().join(format!("advent2020-watch-{}", std::process::id()));
+        fs::write(&path, "1\n").unwrap();
+        let mut watcher = Watcher::new(vec![path.clone()]);
+        let start = Instant::now();
+        let after = |millis| start + Duration::from_millis(millis);
+
+        assert!(!watcher.poll(after(0)));
+        fs::write(&path, "1\n2\n").unwrap();
+        assert!(!watcher.poll(after(100)));
+        fs::write(&path, "1\n2\n3\n").unwrap();
+        assert!(!watcher.poll(after(200)));
+        // Settled 300ms after the last edit
+        assert!(!watcher.poll(after(400)));
+        assert!(watcher.poll(after(500)));
+        assert!(!watcher.poll(after(1000)));
+
+        fs::remove_file(&path).unwrap();
+        assert!(!watcher.poll(after(1100)));
+        assert!(watcher.poll(after(1400)));
+    }
+
+    #[test]
+    fn test_previous_answers() {
+        let mut previous = PreviousAnswers::default();
+
+        let changes = previous.update(&[
+            report(1, Ok(5)),
+            report(2, Err(Error::DayNotImplemented(1))),
+        ]);
+        assert_eq!(changes, vec![AnswerChange::New, AnswerChange::Failed]);
+
+        let changes = previous.update(&[report(1, Ok(5)), report(2, Ok(7))]);
+        assert_eq!(changes, vec![AnswerChange::Unchanged, AnswerChange::New]);
+
+        let changes = previous.update(&[
+            report(1, Err(Error::DayNotImplemented(1))),
+            report(2, Ok(8)),
+        ]);
+        assert_eq!(
+            changes,
+            vec![
+                AnswerChange::Failed,
+                AnswerChange::Changed {
+                    previous: Answer::from(7)
+                }
+            ]
+        );
+        let changes = previous.update(&[report(1, Ok(5))]);
+        assert_eq!(changes, vec![AnswerChange::Unchanged]);
+    }
+}